@@ -0,0 +1,103 @@
+//! Delivery of the alerts of `[alerts]` in the config file: the terminal bell, a desktop
+//! notification escape sequence, and a command hook.
+use std::{
+    io::{Write, stdout},
+    process::Stdio,
+};
+
+use chrono::{DateTime, Local};
+use color_eyre::{Result, eyre::eyre};
+use idacast::data::{alerts::Alert, schedules::Schedule};
+
+use crate::config::{AlertsConfig, Notification};
+
+/// Notify the user of `alert` in every way enabled in `config`.
+///
+/// The command is run with `sh -c` (`cmd /C` on Windows) and isn't waited for. It gets the alert
+/// in `IDACAST_ALERT_TITLE` and `IDACAST_ALERT_BODY`, the mode in `IDACAST_ALERT_MODE` (e.g.
+/// "x-battle"), and the start time of the rotation in RFC 3339 in `IDACAST_ALERT_START`.
+pub(crate) fn deliver(alert: &Alert, config: &AlertsConfig) -> Result<()> {
+    let title = alert.title();
+    let body = format_alert_body(alert);
+
+    let mut escapes = String::new();
+    if config.bell {
+        escapes.push('\x07');
+    }
+    if let Some(notification) = config.notification {
+        escapes.push_str(&format_notification(notification, title, &body));
+    }
+    if !escapes.is_empty() {
+        let mut stdout = stdout();
+        stdout.write_all(escapes.as_bytes())?;
+        stdout.flush()?;
+    }
+
+    if let Some(command) = &config.command {
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+        // The output would be drawn over the TUI
+        let mut child = tokio::process::Command::new(shell)
+            .arg(flag)
+            .arg(command)
+            .env("IDACAST_ALERT_TITLE", title)
+            .env("IDACAST_ALERT_BODY", &body)
+            .env("IDACAST_ALERT_MODE", alert.rotation.mode.to_string())
+            .env(
+                "IDACAST_ALERT_START",
+                alert.rotation.get_start_time().to_rfc3339(),
+            )
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| eyre!("Failed to run the alert command: {err}"))?;
+        tokio::spawn(async move { child.wait().await });
+    }
+    Ok(())
+}
+
+/// The start time and what's played, e.g. "12:00 Clam Blitz: Scorch Gorge, Eeltail Alley".
+pub(crate) fn format_alert_body(alert: &Alert) -> String {
+    let start_time: DateTime<Local> = DateTime::from(alert.rotation.get_start_time());
+    format!(
+        "{} {}",
+        start_time.format("%H:%M"),
+        alert.rotation.details()
+    )
+}
+
+fn format_notification(notification: Notification, title: &str, body: &str) -> String {
+    // Control characters would end the sequence early
+    let sanitize = |text: &str| text.replace(|char: char| char.is_control(), " ");
+    match notification {
+        Notification::Osc9 => format!("\x1b]9;{}: {}\x07", sanitize(title), sanitize(body)),
+        Notification::Osc777 => format!(
+            "\x1b]777;notify;{};{}\x07",
+            sanitize(title).replace(';', ","),
+            sanitize(body)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config::Notification;
+
+    use super::format_notification;
+
+    #[test]
+    fn test_format_notification() {
+        assert_eq!(
+            format_notification(Notification::Osc9, "Big Run", "12:00 Spawning Grounds"),
+            "\x1b]9;Big Run: 12:00 Spawning Grounds\x07"
+        );
+        assert_eq!(
+            format_notification(Notification::Osc777, "a;b", "c\x07d"),
+            "\x1b]777;notify;a,b;c d\x07"
+        );
+    }
+}
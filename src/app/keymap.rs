@@ -0,0 +1,480 @@
+//! Translation of the terminal events into `Action`s, so that every key can be remapped in the
+//! `[keybindings]` section of the config file.
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use color_eyre::{Result, eyre::eyre};
+use crossterm::event::{
+    KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use serde::{Deserialize, Serialize};
+
+/// Everything that can be done from the keyboard or the mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) enum Action {
+    Quit,
+    Refresh,
+    NextTab,
+    PrevTab,
+    /// Open the n-th visible tab, starting from 1.
+    Tab(usize),
+    ScrollUp,
+    ScrollDown,
+    PageUp,
+    PageDown,
+    ScrollTop,
+    ScrollBottom,
+    /// Select the next panel of the tab, e.g. X Battle in the battles tab.
+    NextPanel,
+    PrevPanel,
+    /// Select the next rule or stage of the selected rotation, which `Favorite` and `Dislike`
+    /// apply to.
+    NextItem,
+    PrevItem,
+    /// Mark the selected stage or rule as a favorite, or unmark it.
+    Favorite,
+    /// Mark the selected stage or rule as disliked, or unmark it.
+    Dislike,
+    /// Do nothing, to unbind one of the default keys.
+    Noop,
+}
+
+/// A key along with its modifiers, written like "q", "G", "ctrl-l", "pagedown" or "shift-tab".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Keymap {
+    bindings: HashMap<Key, Action>,
+}
+
+const NAMED_KEYS: [(&str, KeyCode); 14] = [
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("tab", KeyCode::Tab),
+    ("backtab", KeyCode::BackTab),
+    ("esc", KeyCode::Esc),
+    ("enter", KeyCode::Enter),
+    ("space", KeyCode::Char(' ')),
+    ("backspace", KeyCode::Backspace),
+];
+
+const MODIFIERS: [(&str, KeyModifiers); 3] = [
+    ("ctrl", KeyModifiers::CONTROL),
+    ("alt", KeyModifiers::ALT),
+    ("shift", KeyModifiers::SHIFT),
+];
+
+/// Digits open the tabs, see `Keymap::default`.
+const DEFAULT_BINDINGS: [(&str, Action); 31] = [
+    ("q", Action::Quit),
+    ("esc", Action::Quit),
+    ("ctrl-c", Action::Quit),
+    ("r", Action::Refresh),
+    ("f5", Action::Refresh),
+    ("l", Action::NextTab),
+    ("right", Action::NextTab),
+    ("tab", Action::NextTab),
+    ("h", Action::PrevTab),
+    ("left", Action::PrevTab),
+    ("backtab", Action::PrevTab),
+    ("k", Action::ScrollUp),
+    ("up", Action::ScrollUp),
+    ("j", Action::ScrollDown),
+    ("down", Action::ScrollDown),
+    ("pageup", Action::PageUp),
+    ("ctrl-u", Action::PageUp),
+    ("pagedown", Action::PageDown),
+    ("ctrl-d", Action::PageDown),
+    ("space", Action::PageDown),
+    ("g", Action::ScrollTop),
+    ("home", Action::ScrollTop),
+    ("ctrl-l", Action::ScrollTop),
+    ("G", Action::ScrollBottom),
+    ("end", Action::ScrollBottom),
+    ("]", Action::NextPanel),
+    ("[", Action::PrevPanel),
+    ("n", Action::NextItem),
+    ("N", Action::PrevItem),
+    ("f", Action::Favorite),
+    ("d", Action::Dislike),
+];
+
+impl Key {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        // The case of letters already tells whether shift is pressed, and Shift+Tab is BackTab
+        let modifiers = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => modifiers.difference(KeyModifiers::SHIFT),
+            _ => modifiers,
+        };
+        Key { code, modifiers }
+    }
+}
+
+impl From<KeyEvent> for Key {
+    fn from(value: KeyEvent) -> Self {
+        Key::new(value.code, value.modifiers)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+        // A single character is a key, even "-"
+        while rest.chars().count() > 1 {
+            let Some((prefix, suffix)) = rest.split_once('-') else {
+                break;
+            };
+            let Some((_, modifier)) = MODIFIERS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(prefix))
+            else {
+                return Err(format!("unknown modifier {prefix} in {s}"));
+            };
+            modifiers |= *modifier;
+            rest = suffix;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(char), None) => KeyCode::Char(char),
+            _ => NAMED_KEYS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(rest))
+                .map(|(_, code)| *code)
+                .or_else(|| {
+                    let number = rest.strip_prefix(['f', 'F'])?.parse().ok()?;
+                    (1..=12).contains(&number).then_some(KeyCode::F(number))
+                })
+                .ok_or_else(|| format!("unknown key {s}"))?,
+        };
+        let code = match code {
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            code => code,
+        };
+        Ok(Key::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, modifier) in MODIFIERS {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{name}-")?;
+            }
+        }
+        match self.code {
+            KeyCode::F(number) => write!(f, "f{number}"),
+            code => match NAMED_KEYS.iter().find(|(_, named)| *named == code) {
+                Some((name, _)) => write!(f, "{name}"),
+                None => match code {
+                    KeyCode::Char(char) => write!(f, "{char}"),
+                    code => write!(f, "{code:?}"),
+                },
+            },
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let action = match s {
+            "quit" => Action::Quit,
+            "refresh" => Action::Refresh,
+            "next-tab" => Action::NextTab,
+            "prev-tab" => Action::PrevTab,
+            "scroll-up" => Action::ScrollUp,
+            "scroll-down" => Action::ScrollDown,
+            "page-up" => Action::PageUp,
+            "page-down" => Action::PageDown,
+            "scroll-top" => Action::ScrollTop,
+            "scroll-bottom" => Action::ScrollBottom,
+            "next-panel" => Action::NextPanel,
+            "prev-panel" => Action::PrevPanel,
+            "next-item" => Action::NextItem,
+            "prev-item" => Action::PrevItem,
+            "favorite" => Action::Favorite,
+            "dislike" => Action::Dislike,
+            "noop" => Action::Noop,
+            _ => match s.strip_prefix("tab-").and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => Action::Tab(n),
+                _ => return Err(format!("unknown action {s}")),
+            },
+        };
+        Ok(action)
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::Quit => write!(f, "quit"),
+            Action::Refresh => write!(f, "refresh"),
+            Action::NextTab => write!(f, "next-tab"),
+            Action::PrevTab => write!(f, "prev-tab"),
+            Action::Tab(n) => write!(f, "tab-{n}"),
+            Action::ScrollUp => write!(f, "scroll-up"),
+            Action::ScrollDown => write!(f, "scroll-down"),
+            Action::PageUp => write!(f, "page-up"),
+            Action::PageDown => write!(f, "page-down"),
+            Action::ScrollTop => write!(f, "scroll-top"),
+            Action::ScrollBottom => write!(f, "scroll-bottom"),
+            Action::NextPanel => write!(f, "next-panel"),
+            Action::PrevPanel => write!(f, "prev-panel"),
+            Action::NextItem => write!(f, "next-item"),
+            Action::PrevItem => write!(f, "prev-item"),
+            Action::Favorite => write!(f, "favorite"),
+            Action::Dislike => write!(f, "dislike"),
+            Action::Noop => write!(f, "noop"),
+        }
+    }
+}
+
+impl TryFrom<String> for Action {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Action> for String {
+    fn from(value: Action) -> Self {
+        value.to_string()
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut bindings: HashMap<Key, Action> = DEFAULT_BINDINGS
+            .iter()
+            .map(|(key, action)| (key.parse().expect("invalid default key"), *action))
+            .collect();
+        for n in 1..=9 {
+            bindings.insert(
+                Key::new(
+                    KeyCode::Char(char::from_digit(n, 10).unwrap_or_default()),
+                    KeyModifiers::NONE,
+                ),
+                Action::Tab(n as usize),
+            );
+        }
+        Keymap { bindings }
+    }
+}
+
+impl Keymap {
+    /// The default keymap, with the keys of `overrides` bound to their action instead.
+    pub fn new<'a>(overrides: impl IntoIterator<Item = (&'a String, &'a Action)>) -> Result<Self> {
+        let mut keymap = Keymap::default();
+        for (key, action) in overrides {
+            let key: Key = key.parse().map_err(|err| eyre!("keybindings: {err}"))?;
+            keymap.bindings.insert(key, *action);
+        }
+        Ok(keymap)
+    }
+
+    pub fn key_action(&self, event: KeyEvent) -> Option<Action> {
+        // Some terminals report releases as well, only react once
+        if event.kind == KeyEventKind::Release {
+            return None;
+        }
+        self.bindings
+            .get(&Key::from(event))
+            .copied()
+            .filter(|action| *action != Action::Noop)
+    }
+
+    /// The mouse can only scroll: the wheel scrolls, and the right button goes back to the top.
+    pub fn mouse_action(&self, event: MouseEvent) -> Option<Action> {
+        match event.kind {
+            MouseEventKind::ScrollUp => Some(Action::ScrollUp),
+            MouseEventKind::ScrollDown => Some(Action::ScrollDown),
+            MouseEventKind::Down(MouseButton::Right) => Some(Action::ScrollTop),
+            _ => None,
+        }
+    }
+
+    /// A key bound to `action`, to be displayed as a hint. Keys without modifiers and single
+    /// characters are preferred.
+    pub fn hint(&self, action: Action) -> Option<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| **bound == action)
+            .map(|(key, _)| key.to_string())
+            .min_by_key(|key| (key.contains('-'), key.chars().count(), key.clone()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crossterm::event::{
+        KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+
+    use super::{Action, Key, Keymap};
+
+    fn key_event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            "ctrl-l".parse(),
+            Ok(Key::new(KeyCode::Char('l'), KeyModifiers::CONTROL))
+        );
+        assert_eq!(
+            "Ctrl-Alt-PageDown".parse(),
+            Ok(Key::new(
+                KeyCode::PageDown,
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            ))
+        );
+        assert_eq!("shift-tab".parse::<Key>(), "backtab".parse::<Key>(),);
+        assert_eq!(
+            "-".parse(),
+            Ok(Key::new(KeyCode::Char('-'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "f5".parse(),
+            Ok(Key::new(KeyCode::F(5), KeyModifiers::NONE))
+        );
+        assert!("hyper-x".parse::<Key>().is_err());
+        assert!("pgdn".parse::<Key>().is_err());
+
+        for key in [
+            "q",
+            "G",
+            "ctrl-l",
+            "alt-pagedown",
+            "backtab",
+            "space",
+            "f12",
+        ] {
+            assert_eq!(key.parse::<Key>().unwrap().to_string(), key);
+        }
+    }
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!("next-tab".parse(), Ok(Action::NextTab));
+        assert_eq!("tab-3".parse(), Ok(Action::Tab(3)));
+        assert!("tab-0".parse::<Action>().is_err());
+        assert!("jump".parse::<Action>().is_err());
+        assert_eq!(Action::ScrollBottom.to_string(), "scroll-bottom");
+        assert_eq!("dislike".parse(), Ok(Action::Dislike));
+        assert_eq!(Action::PrevItem.to_string(), "prev-item");
+    }
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = Keymap::default();
+        let action = |code, modifiers| keymap.key_action(key_event(code, modifiers));
+        assert_eq!(
+            action(KeyCode::Char('j'), KeyModifiers::NONE),
+            Some(Action::ScrollDown)
+        );
+        assert_eq!(
+            action(KeyCode::Down, KeyModifiers::NONE),
+            Some(Action::ScrollDown)
+        );
+        assert_eq!(
+            action(KeyCode::Char('3'), KeyModifiers::NONE),
+            Some(Action::Tab(3))
+        );
+        assert_eq!(
+            action(KeyCode::End, KeyModifiers::NONE),
+            Some(Action::ScrollBottom)
+        );
+        // Terminals send shifted letters with or without the shift modifier
+        assert_eq!(
+            action(KeyCode::Char('G'), KeyModifiers::SHIFT),
+            Some(Action::ScrollBottom)
+        );
+        assert_eq!(
+            action(KeyCode::Char('G'), KeyModifiers::NONE),
+            Some(Action::ScrollBottom)
+        );
+        assert_eq!(
+            action(KeyCode::BackTab, KeyModifiers::SHIFT),
+            Some(Action::PrevTab)
+        );
+        assert_eq!(
+            action(KeyCode::Char('c'), KeyModifiers::CONTROL),
+            Some(Action::Quit)
+        );
+        assert_eq!(action(KeyCode::Char('x'), KeyModifiers::NONE), None);
+
+        let mut release = key_event(KeyCode::Char('q'), KeyModifiers::NONE);
+        release.kind = KeyEventKind::Release;
+        assert_eq!(keymap.key_action(release), None);
+
+        assert_eq!(keymap.hint(Action::ScrollDown).as_deref(), Some("j"));
+        assert_eq!(keymap.hint(Action::ScrollTop).as_deref(), Some("g"));
+    }
+
+    #[test]
+    fn test_keymap_overrides() {
+        let overrides = BTreeMap::from([
+            ("x".to_string(), Action::Quit),
+            ("q".to_string(), Action::Noop),
+            ("ctrl-n".to_string(), Action::NextTab),
+        ]);
+        let keymap = Keymap::new(&overrides).unwrap();
+        let action = |code, modifiers| keymap.key_action(key_event(code, modifiers));
+        assert_eq!(
+            action(KeyCode::Char('x'), KeyModifiers::NONE),
+            Some(Action::Quit)
+        );
+        assert_eq!(action(KeyCode::Char('q'), KeyModifiers::NONE), None);
+        assert_eq!(
+            action(KeyCode::Char('n'), KeyModifiers::CONTROL),
+            Some(Action::NextTab)
+        );
+        // Other defaults are kept
+        assert_eq!(action(KeyCode::Esc, KeyModifiers::NONE), Some(Action::Quit));
+
+        let invalid = BTreeMap::from([("meta-x".to_string(), Action::Quit)]);
+        assert!(Keymap::new(&invalid).is_err());
+    }
+
+    #[test]
+    fn test_mouse_action() {
+        let mouse_event = |kind| MouseEvent {
+            kind,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        };
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.mouse_action(mouse_event(MouseEventKind::ScrollDown)),
+            Some(Action::ScrollDown)
+        );
+        assert_eq!(
+            keymap.mouse_action(mouse_event(MouseEventKind::Down(MouseButton::Right))),
+            Some(Action::ScrollTop)
+        );
+        assert_eq!(
+            keymap.mouse_action(mouse_event(MouseEventKind::Moved)),
+            None
+        );
+    }
+}
@@ -1,45 +1,128 @@
+mod alerts;
+pub(crate) mod keymap;
+pub(crate) mod theme;
 mod ui;
-use std::sync::LazyLock;
+use std::{
+    cmp::{max, min},
+    path::PathBuf,
+    sync::{Arc, LazyLock},
+};
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
-use cached::{DiskCache, IOCached};
 use chrono::{DateTime, Duration, Local, Utc};
-use color_eyre::{Result, eyre::Report};
-use crossterm::event::{self, Event, EventStream, KeyEvent, MouseButton, MouseEvent};
+use color_eyre::{
+    Result,
+    eyre::{Report, eyre},
+};
+use crossterm::event::{Event, EventStream, KeyEvent, MouseEvent};
 use data::schedules::{self};
 use futures::{StreamExt, future::FutureExt};
 use ratatui::DefaultTerminal;
-use ratatui::style::{Color, Stylize};
+use ratatui::style::Stylize;
 
-use strum::IntoEnumIterator;
-use strum_macros::{Display, EnumIter, FromRepr};
-use tokio::sync::mpsc::UnboundedSender;
+use strum_macros::{Display, EnumIter, EnumString};
+use tokio::sync::{Notify, OnceCell, mpsc::UnboundedSender};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
-use data::schedules::Schedules;
+use data::festivals::{Festival, Festivals, Region};
+use data::gear::{CoopReward, GearShop};
+use data::query::{Mode, ScheduleMatch};
+use data::schedules::{NameID, Schedule, Schedules};
 
-use crate::data::{self, get_schedules};
+use idacast::{
+    cache::{CacheEntry, CacheInfo, CacheStore},
+    data::{
+        self, DataError, get_coop_reward, get_dictionary, get_festivals, get_gear_shop,
+        get_schedules_if_modified,
+        source::{DEFAULT_BASE_URL, DataSource, Fetched},
+        translation::TranslationDictionary,
+    },
+};
 use ui::draw;
 
+use keymap::{Action, Keymap};
+
+use theme::Theme;
+
+use crate::config::{
+    AlertsConfig, Config, Preference, PreferenceMarks, Preferences, RefreshPolicy,
+};
+
 // Cache
-static CACHE_STORE: LazyLock<DiskCache<String, Schedules>> = LazyLock::new(|| {
-    DiskCache::new(CACHE_STORE_NAME)
-        .set_lifespan(CACHE_STORE_TTL.to_std().unwrap())
-        .set_refresh(false)
-        .build()
-        .unwrap()
-});
-
-// Update the schedules every 4 hours. There's no reason to change it.
-const AUTO_UPDATE_INTERVAL: Duration = Duration::hours(4);
-const CACHE_STORE_TTL: Duration = Duration::hours(3);
+/// A cache store opened on first use. The error is kept to be reported each time it's used.
+type LazyCacheStore<V> = LazyLock<Result<CacheStore<V>, DataError>>;
+
+static CACHE_STORE: LazyCacheStore<Schedules> = LazyLock::new(|| CacheStore::new(CACHE_STORE_NAME));
+static GEAR_CACHE_STORE: LazyCacheStore<GearShop> =
+    LazyLock::new(|| CacheStore::new(GEAR_CACHE_STORE_NAME));
+static COOP_CACHE_STORE: LazyCacheStore<CoopReward> =
+    LazyLock::new(|| CacheStore::new(COOP_CACHE_STORE_NAME));
+static FESTIVALS_CACHE_STORE: LazyCacheStore<Festivals> =
+    LazyLock::new(|| CacheStore::new(FESTIVALS_CACHE_STORE_NAME));
+
+// The schedules are updated when they expire, see `CacheEntry::with_schedules`. Failed updates
+// are retried with an exponential backoff, starting from `RETRY_BASE_DELAY`. Both are the
+// defaults of `RefreshPolicy`.
+pub(crate) const RETRY_BASE_DELAY: Duration = Duration::seconds(15);
+pub(crate) const RETRY_MAX_DELAY: Duration = Duration::minutes(30);
 pub(crate) const CACHE_STORE_NAME: &str = "IDACAST_CACHE";
+pub(crate) const GEAR_CACHE_STORE_NAME: &str = "IDACAST_GEAR_CACHE";
+pub(crate) const COOP_CACHE_STORE_NAME: &str = "IDACAST_COOP_CACHE";
+pub(crate) const FESTIVALS_CACHE_STORE_NAME: &str = "IDACAST_FESTIVALS_CACHE";
+/// Number of schedules (or stages, festivals) scrolled by `Action::PageUp` and `PageDown`.
+const PAGE_LENGTH: usize = 5;
+/// Panels of the battles tab, from the top left to the bottom right.
+const BATTLE_PANELS: [Mode; 4] = [
+    Mode::AnarchySeries,
+    Mode::AnarchyOpen,
+    Mode::XBattle,
+    Mode::Regular,
+];
 
 pub(crate) struct App {
     exit: bool,
     locale: Option<String>,
+    /// Key of the documents of `source` in `locale` in the caches, see `cache_key`.
+    cache_key: String,
+    region: Region,
+    /// `None` in offline mode, where everything is loaded from the cache.
+    source: Option<Arc<dyn DataSource>>,
+    refresh_policy: RefreshPolicy,
+    /// Visible tabs, in the order of the header.
+    tabs: Vec<AppScreen>,
+    theme: Theme,
+    keymap: Keymap,
+    /// The preferences of the config file, with the marks saved by the TUI applied.
+    preferences: Preferences,
+    /// The `[preferences]` of the config file, which the saved marks are relative to.
+    config_preferences: Preferences,
+    /// Where the preferences marked in the TUI are saved, see `PreferenceMarks`.
+    preferences_path: PathBuf,
+    /// Why the preferences marked in the TUI couldn't be saved, shown in the footer.
+    preferences_error: Option<Report>,
+    alerts: AlertsConfig,
+    /// Alerts firing until this time have been delivered.
+    alerts_checked_at: DateTime<Utc>,
+    /// The last alert, displayed until its rotation starts.
+    last_alert: Option<(DateTime<Utc>, String)>,
+    /// Why the last alert couldn't be delivered, shown in the footer.
+    alert_error: Option<Report>,
     app_ui: AppUI,
     refresh_state: RefreshState,
     schedules: schedules::Schedules,
+    schedules_cache_info: Option<CacheInfo>,
+    next_update: Option<ScheduledUpdate>,
+    /// Wakes up the auto update task for a manual refresh.
+    manual_refresh: Arc<Notify>,
+    gear_shop: GearShop,
+    coop_reward: Option<CoopReward>,
+    festivals: Festivals,
+    /// Why the supplementary data couldn't be loaded, shown in their tabs rather than in the
+    /// error panel, which is about the schedules.
+    gear_shop_error: Option<Report>,
+    coop_reward_error: Option<Report>,
+    festivals_error: Option<Report>,
     appevents_tx: UnboundedSender<AppEvent>,
     appevents_rx: UnboundedReceiverStream<AppEvent>,
     termevents_rx: EventStream,
@@ -52,12 +135,18 @@ struct AppUI {
     work: Work,
     challenges: Challenges,
     fest: Fest,
+    stages: Stages,
+    festivals: FestivalsUI,
 }
 
 #[derive(Default)]
 struct Battles {
     scroll_offset: usize,
     schedules_count: usize,
+    /// Index of the selected panel in `BATTLE_PANELS`.
+    panel: usize,
+    /// Index of the selected item of the first displayed rotation, its rule then its stages.
+    item: usize,
 }
 
 #[derive(Default)]
@@ -75,46 +164,77 @@ struct Fest {
     scroll_offset: usize,
 }
 
-#[derive(Default, EnumIter, FromRepr, Display, Clone, Copy, PartialEq, Eq)]
+#[derive(Default)]
+struct Stages {
+    scroll_offset: usize,
+}
+
+#[derive(Default)]
+struct FestivalsUI {
+    selected: usize,
+}
+
+#[derive(
+    Debug,
+    Default,
+    EnumIter,
+    EnumString,
+    Display,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(ascii_case_insensitive)]
 pub(crate) enum AppScreen {
     #[default]
     Battles,
     Work,
     Challenges,
     Fest,
+    Stages,
+    Shop,
+    Festivals,
 }
 
 impl AppScreen {
-    fn to_tab_title<'a>(value: Self) -> ratatui::text::Line<'a> {
-        let text = value.to_string();
-        let color = match value {
-            AppScreen::Battles => Color::LightGreen,
-            AppScreen::Work => Color::LightRed,
-            AppScreen::Challenges => Color::LightMagenta,
-            AppScreen::Fest => Color::LightBlue,
-        };
-        format!(" {} ", text).fg(color).bg(Color::Black).into()
-    }
-
-    fn last() -> Self {
-        AppScreen::iter().next_back().unwrap_or_default()
+    fn to_tab_title<'a>(self, theme: &Theme) -> ratatui::text::Line<'a> {
+        format!(" {} ", self)
+            .fg(theme.tabs.get(self))
+            .bg(theme.tab_background)
+            .into()
     }
 
-    fn next(self) -> Self {
-        let cur = self as usize;
-        Self::from_repr(cur.wrapping_add(1)).unwrap_or_default()
+    /// The tab after `self` in `tabs`, wrapping around. The first tab if `self` isn't in `tabs`.
+    fn next(self, tabs: &[AppScreen]) -> Self {
+        match tabs.iter().position(|tab| *tab == self) {
+            Some(index) => tabs[(index + 1) % tabs.len()],
+            None => tabs.first().copied().unwrap_or(self),
+        }
     }
 
-    fn prev(self) -> Self {
-        let cur = self as usize;
-        Self::from_repr(cur.wrapping_sub(1)).unwrap_or(Self::last())
+    fn prev(self, tabs: &[AppScreen]) -> Self {
+        match tabs.iter().position(|tab| *tab == self) {
+            Some(index) => tabs[(index + tabs.len() - 1) % tabs.len()],
+            None => tabs.first().copied().unwrap_or(self),
+        }
     }
 }
 
 #[derive(Debug)]
 enum AppEvent {
     Refresh(RefreshState),
-    ScheduleLoad(Schedules),
+    UpdateScheduled(ScheduledUpdate),
+    ScheduleLoad(Box<CacheEntry<Schedules>>),
+    GearLoad(Box<GearShop>),
+    CoopRewardLoad(Box<CoopReward>),
+    FestivalsLoad(Box<Festivals>),
+    GearError(Report),
+    CoopRewardError(Report),
+    FestivalsError(Report),
 }
 
 #[derive(Debug, Default)]
@@ -125,108 +245,483 @@ enum RefreshState {
     Error(Report),
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ScrollOperation {
     Up,
     Down,
-    Reset,
+    PageUp,
+    PageDown,
+    Top,
+    Bottom,
+}
+
+impl ScrollOperation {
+    /// The scroll offset after this operation, between 0 and `upper`.
+    fn apply(self, offset: usize, upper: usize) -> usize {
+        let offset = match self {
+            ScrollOperation::Up => offset.saturating_sub(1),
+            ScrollOperation::Down => offset.saturating_add(1),
+            ScrollOperation::PageUp => offset.saturating_sub(PAGE_LENGTH),
+            ScrollOperation::PageDown => offset.saturating_add(PAGE_LENGTH),
+            ScrollOperation::Top => 0,
+            ScrollOperation::Bottom => upper,
+        };
+        offset.min(upper)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ScheduledUpdate {
+    time: DateTime<Utc>,
+    /// Number of consecutive failed updates, 0 if the last one succeeded.
+    failed_attempts: u32,
 }
 
-fn format_option_string(locale: &Option<String>) -> String {
-    match locale {
-        Some(locale) => locale.clone(),
-        None => "default".to_string(),
+/// How long to wait before the next auto update, given the time when the schedules expire.
+fn get_auto_update_delay(
+    expires_at: DateTime<Utc>,
+    time: DateTime<Utc>,
+    policy: &RefreshPolicy,
+) -> Duration {
+    // Expired entries are only loaded when they can't be refreshed, don't retry right away
+    max(expires_at - time, policy.min_interval())
+}
+
+/// Exponential backoff after `failed_attempts` failures, capped at the maximum delay of `policy`.
+///
+/// Half of the delay is randomized with `jitter` (between 0 and 1), so that clients that failed
+/// at the same time don't retry at the same time.
+fn get_retry_delay(failed_attempts: u32, jitter: f64, policy: &RefreshPolicy) -> Duration {
+    let max_delay = policy.max_retry_delay();
+    let backoff = policy
+        .retry_delay()
+        .checked_mul(2_i32.saturating_pow(failed_attempts.saturating_sub(1)))
+        .map_or(max_delay, |backoff| min(backoff, max_delay));
+    let half = backoff / 2;
+    half + Duration::milliseconds((half.num_milliseconds() as f64 * jitter.clamp(0.0, 1.0)) as i64)
+}
+
+/// The key of the documents in `lang` loaded from `source` in the caches. Documents from other
+/// sources than splatoon3.ink, e.g. the samples of a local directory, are cached apart.
+pub(crate) fn cache_key(source: Option<&str>, lang: &Option<String>) -> String {
+    let lang = lang.as_deref().unwrap_or("default");
+    match source {
+        None | Some(DEFAULT_BASE_URL) => lang.to_string(),
+        Some(source) => format!("{lang}@{source}"),
     }
 }
 
+fn open_cache_store<V>(store: &'static LazyCacheStore<V>) -> Result<&'static CacheStore<V>> {
+    LazyLock::force(store)
+        .as_ref()
+        .map_err(|err| Report::new(err.clone()))
+}
+
+/// The cache of the schedules, shared by the TUI and the subcommands. It's locked by the first
+/// process that opens it.
+pub(crate) fn schedules_cache_store() -> Result<&'static CacheStore<Schedules>> {
+    open_cache_store(&CACHE_STORE)
+}
+
+/// The dictionary of `lang` in `cell`, fetched from `source` by the first caller.
+async fn load_dictionary<'a>(
+    cell: &'a OnceCell<Option<TranslationDictionary>>,
+    source: &dyn DataSource,
+    lang: Option<String>,
+) -> Result<Option<&'a TranslationDictionary>> {
+    let dict = cell
+        .get_or_try_init(|| get_dictionary(source, lang))
+        .await?;
+    Ok(dict.as_ref())
+}
+
+/// Load the schedules in `lang` from `cache_store` at `cache_key`, unless `use_cache` is false or the entry is
+/// stale, in which case it's revalidated with `source` and cached again. `source` is `None` in
+/// offline mode, where the cache is always used, however old it is.
+///
+/// `on_stale` is called with the stale entry before it's revalidated. Returns the entry, and
+/// whether it's from the cache.
+pub(crate) async fn load_schedules(
+    cache_store: &CacheStore<Schedules>,
+    source: Option<&dyn DataSource>,
+    lang: Option<String>,
+    cache_key: &str,
+    use_cache: bool,
+    on_stale: impl FnOnce(&CacheEntry<Schedules>),
+) -> Result<(CacheEntry<Schedules>, bool)> {
+    let cached_opt = cache_store.get(cache_key)?;
+
+    if let Some(entry) = cached_opt
+        .as_ref()
+        .filter(|_| use_cache || source.is_none())
+    {
+        if !entry.is_stale(Utc::now()) || source.is_none() {
+            return Ok((entry.clone(), true));
+        }
+        on_stale(entry);
+    }
+    let Some(source) = source else {
+        return Err(eyre!("No cached schedules available in offline mode"));
+    };
+
+    // Even when the cache isn't used, a conditional request avoids downloading the same data
+    let validators = cached_opt
+        .as_ref()
+        .map(|entry| entry.validators.clone())
+        .unwrap_or_default();
+    let (entry, cache_hit) = match get_schedules_if_modified(source, lang, &validators).await? {
+        Fetched::Modified(document) => (CacheEntry::with_schedules(document), false),
+        Fetched::NotModified { max_age } => match cached_opt {
+            Some(entry) => (entry.renew(max_age), true),
+            None => return Err(eyre!("Schedules are not modified, but they're not cached")),
+        },
+    };
+    cache_store.set(cache_key, entry.clone())?;
+    Ok((entry, cache_hit))
+}
+
 impl App {
-    pub fn new(locale: Option<String>) -> Self {
+    pub fn new(
+        config: Config,
+        preferences_path: PathBuf,
+        source: Option<Arc<dyn DataSource>>,
+    ) -> Result<Self> {
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<AppEvent>();
-        App {
+        let mut preferences = config.preferences.clone();
+        preferences.apply(&PreferenceMarks::load(&preferences_path)?);
+        Ok(App {
             exit: false,
-            locale,
-            app_ui: AppUI::default(),
+            cache_key: cache_key(config.source.as_deref(), &config.language),
+            locale: config.language,
+            region: config.region,
+            source,
+            refresh_policy: config.refresh,
+            tabs: config.tabs.visible.clone(),
+            theme: config.theme.build()?,
+            keymap: Keymap::new(&config.keybindings)?,
+            preferences,
+            config_preferences: config.preferences,
+            preferences_path,
+            preferences_error: None,
+            alerts: config.alerts,
+            alerts_checked_at: Utc::now(),
+            last_alert: None,
+            alert_error: None,
+            app_ui: AppUI {
+                current_screen: config.tabs.startup_tab(),
+                ..Default::default()
+            },
             refresh_state: RefreshState::Pending,
             termevents_rx: EventStream::new(),
             schedules: Schedules::default(),
+            schedules_cache_info: None,
+            next_update: None,
+            manual_refresh: Arc::new(Notify::new()),
+            gear_shop: GearShop::default(),
+            coop_reward: None,
+            festivals: Festivals::default(),
+            gear_shop_error: None,
+            coop_reward_error: None,
+            festivals_error: None,
             appevents_tx: tx,
             appevents_rx: UnboundedReceiverStream::new(rx),
-        }
+        })
     }
 
-    fn refresh_schedule(
+    fn refresh_supplementary(
         tx: UnboundedSender<AppEvent>,
+        source: Option<Arc<dyn DataSource>>,
         lang: Option<String>,
+        cache_key: String,
         cached: bool,
-    ) -> Result<()> {
-        tokio::spawn(App::handle_refresh(tx, lang, cached));
-
-        Ok(())
+    ) {
+        // Shared by the documents, and only fetched if one of them isn't cached
+        let dictionary = Arc::new(OnceCell::new());
+        let (gear_tx, gear_source, gear_lang, gear_key, gear_dictionary) = (
+            tx.clone(),
+            source.clone(),
+            lang.clone(),
+            cache_key.clone(),
+            dictionary.clone(),
+        );
+        tokio::spawn(async move {
+            App::handle_cached_refresh(
+                gear_tx,
+                &gear_key,
+                cached,
+                &GEAR_CACHE_STORE,
+                gear_source.as_deref().map(|source| async move {
+                    let dict = load_dictionary(&gear_dictionary, source, gear_lang).await?;
+                    get_gear_shop(source, dict).await
+                }),
+                AppEvent::GearLoad,
+                AppEvent::GearError,
+            )
+            .await
+        });
+        let (coop_tx, coop_source, coop_lang, coop_key, coop_dictionary) = (
+            tx.clone(),
+            source.clone(),
+            lang.clone(),
+            cache_key.clone(),
+            dictionary.clone(),
+        );
+        tokio::spawn(async move {
+            App::handle_cached_refresh(
+                coop_tx,
+                &coop_key,
+                cached,
+                &COOP_CACHE_STORE,
+                coop_source.as_deref().map(|source| async move {
+                    let dict = load_dictionary(&coop_dictionary, source, coop_lang).await?;
+                    get_coop_reward(source, dict).await
+                }),
+                AppEvent::CoopRewardLoad,
+                AppEvent::CoopRewardError,
+            )
+            .await
+        });
+        tokio::spawn(async move {
+            App::handle_cached_refresh(
+                tx,
+                &cache_key,
+                cached,
+                &FESTIVALS_CACHE_STORE,
+                source.as_deref().map(|source| async move {
+                    let dict = load_dictionary(&dictionary, source, lang).await?;
+                    get_festivals(source, dict).await
+                }),
+                AppEvent::FestivalsLoad,
+                AppEvent::FestivalsError,
+            )
+            .await
+        });
     }
 
+    /// Load the schedules from the cache if possible, otherwise fetch them from the data source.
+    ///
+    /// Stale cache entries are displayed while they're being revalidated, so that there's something
+    /// to show when the data source can't be reached.
+    ///
+    /// Returns the time when the loaded schedules expire, if any.
     async fn handle_refresh(
         tx: UnboundedSender<AppEvent>,
+        source: Option<Arc<dyn DataSource>>,
         lang: Option<String>,
+        cache_key: &str,
         use_cache: bool,
-    ) -> Result<()> {
+    ) -> Result<Option<DateTime<Utc>>> {
         tx.send(AppEvent::Refresh(RefreshState::Pending))?;
 
-        let cached_opt = App::get_cache(&format_option_string(&lang))?;
-        let fetch_online = async || get_schedules(lang).await;
-        let mut cache_hit = false;
-
-        let schedules_result: Result<Schedules> = if !use_cache {
-            Ok(fetch_online().await?)
-        } else if let Some(schedules) = cached_opt {
-            cache_hit = true;
-            Ok(schedules)
-        } else {
-            Ok(fetch_online().await?)
+        // Keep displaying stale entries while revalidating them
+        let loaded = match schedules_cache_store() {
+            Ok(cache_store) => {
+                load_schedules(
+                    cache_store,
+                    source.as_deref(),
+                    lang,
+                    cache_key,
+                    use_cache,
+                    |entry| {
+                        let _ = tx.send(AppEvent::ScheduleLoad(Box::new(entry.clone())));
+                    },
+                )
+                .await
+            }
+            Err(err) => Err(err),
         };
 
-        match schedules_result {
-            Ok(schedules) => {
-                tx.send(AppEvent::ScheduleLoad(schedules))?;
+        match loaded {
+            Ok((entry, cache_hit)) => {
+                let expires_at = entry.info.expires_at;
+                tx.send(AppEvent::ScheduleLoad(Box::new(entry)))?;
                 tx.send(AppEvent::Refresh(RefreshState::Completed(
                     Local::now(),
                     cache_hit,
                 )))?;
+                Ok(Some(expires_at))
             }
             Err(err) => {
                 tx.send(AppEvent::Refresh(RefreshState::Error(err)))?;
+                Ok(None)
             }
         }
-        Ok(())
     }
 
-    fn get_cache(lang: &String) -> Result<Option<Schedules>> {
-        Ok(CACHE_STORE.cache_get(lang)?)
+    /// Refresh the supplementary data (gear, Salmon Run rewards, etc.) that is shown alongside
+    /// the schedules, following the same caching policy as `handle_refresh`.
+    ///
+    /// `fetch_online` is `None` in offline mode, and is only awaited when the cache is missing or
+    /// stale. Failures are sent with `on_error`, and don't change the `RefreshState` of the
+    /// schedules.
+    async fn handle_cached_refresh<T, Fut>(
+        tx: UnboundedSender<AppEvent>,
+        cache_key: &str,
+        use_cache: bool,
+        cache_store: &'static LazyCacheStore<T>,
+        fetch_online: Option<Fut>,
+        on_load: fn(Box<T>) -> AppEvent,
+        on_error: fn(Report) -> AppEvent,
+    ) -> Result<()>
+    where
+        T: Serialize + DeserializeOwned + Clone,
+        Fut: Future<Output = Result<T>>,
+    {
+        let cache_store = match open_cache_store(cache_store) {
+            Ok(cache_store) => cache_store,
+            Err(err) => {
+                tx.send(on_error(err))?;
+                return Ok(());
+            }
+        };
+        let cached_opt = if use_cache || fetch_online.is_none() {
+            cache_store.get(cache_key)?
+        } else {
+            None
+        };
+
+        if let Some(entry) = cached_opt {
+            let is_stale = entry.is_stale(Utc::now());
+            tx.send(on_load(Box::new(entry.value)))?;
+            if !is_stale {
+                return Ok(());
+            }
+        }
+        let Some(fetch_online) = fetch_online else {
+            return Ok(());
+        };
+
+        match fetch_online.await {
+            Ok(value) => {
+                let entry = CacheEntry::new(value);
+                tx.send(on_load(Box::new(entry.value.clone())))?;
+                cache_store.set(cache_key, entry)?;
+            }
+            Err(err) => {
+                tx.send(on_error(err))?;
+            }
+        }
+        Ok(())
     }
 
     /// runs the application's main loop until the user quits
     pub async fn run(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
-        App::register_auto_update(self.appevents_tx.clone(), self.locale.clone())?;
+        App::register_auto_update(
+            self.appevents_tx.clone(),
+            self.source.clone(),
+            self.locale.clone(),
+            self.cache_key.clone(),
+            self.manual_refresh.clone(),
+            self.refresh_policy,
+        )?;
         while !self.exit {
             terminal.draw(|frame| draw(self, frame))?;
             self.handle_events().await?;
+            self.check_alerts();
         }
         Ok(())
     }
 
-    fn register_auto_update(tx: UnboundedSender<AppEvent>, locale: Option<String>) -> Result<()> {
-        tokio::spawn(App::handle_auto_update(tx.clone(), locale.clone()));
+    fn register_auto_update(
+        tx: UnboundedSender<AppEvent>,
+        source: Option<Arc<dyn DataSource>>,
+        locale: Option<String>,
+        cache_key: String,
+        manual_refresh: Arc<Notify>,
+        policy: RefreshPolicy,
+    ) -> Result<()> {
+        tokio::spawn(App::handle_auto_update(
+            tx.clone(),
+            source,
+            locale.clone(),
+            cache_key,
+            manual_refresh,
+            policy,
+        ));
 
         Ok(())
     }
 
+    /// Keep the data up to date: refresh when the schedules expire, retry when it fails, or
+    /// refresh right away when requested by the user, which cancels the pending update.
+    ///
+    /// When auto updates are disabled by `policy`, only the refreshes requested by the user are
+    /// done.
     async fn handle_auto_update(
         tx: UnboundedSender<AppEvent>,
+        source: Option<Arc<dyn DataSource>>,
         locale: Option<String>,
+        cache_key: String,
+        manual_refresh: Arc<Notify>,
+        policy: RefreshPolicy,
     ) -> Result<()> {
-        let mut interval = tokio::time::interval(AUTO_UPDATE_INTERVAL.to_std()?);
-
+        let mut use_cache = true;
+        let mut failed_attempts: u32 = 0;
         loop {
-            interval.tick().await;
-            App::refresh_schedule(tx.clone(), locale.clone(), true)?;
+            App::refresh_supplementary(
+                tx.clone(),
+                source.clone(),
+                locale.clone(),
+                cache_key.clone(),
+                use_cache,
+            );
+            let refreshed = App::handle_refresh(
+                tx.clone(),
+                source.clone(),
+                locale.clone(),
+                &cache_key,
+                use_cache,
+            )
+            .await;
+            if !policy.auto_update {
+                if let Err(err) = refreshed {
+                    tx.send(AppEvent::Refresh(RefreshState::Error(err)))?;
+                }
+                manual_refresh.notified().await;
+                use_cache = false;
+                continue;
+            }
+            let delay = match refreshed {
+                Ok(Some(expires_at)) => {
+                    failed_attempts = 0;
+                    get_auto_update_delay(expires_at, Utc::now(), &policy)
+                }
+                failed => {
+                    if let Err(err) = failed {
+                        // e.g. the cache can't be accessed, keep the task alive to retry
+                        tx.send(AppEvent::Refresh(RefreshState::Error(err)))?;
+                    }
+                    failed_attempts = failed_attempts.saturating_add(1);
+                    get_retry_delay(failed_attempts, rand::random(), &policy)
+                }
+            };
+            tx.send(AppEvent::UpdateScheduled(ScheduledUpdate {
+                time: Utc::now() + delay,
+                failed_attempts,
+            }))?;
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay.to_std()?) => {
+                    use_cache = true;
+                }
+                _ = manual_refresh.notified() => {
+                    use_cache = false;
+                    failed_attempts = 0;
+                }
+            }
+        }
+    }
+
+    /// Deliver the alerts that fired since the last check, whatever the tab.
+    fn check_alerts(&mut self) {
+        let time_now = Utc::now();
+        let since = std::mem::replace(&mut self.alerts_checked_at, time_now);
+        for alert in self.schedules.alerts(&self.alerts.rules, since, time_now) {
+            self.last_alert = Some((
+                alert.rotation.get_start_time(),
+                format!("{}: {}", alert.title(), alerts::format_alert_body(&alert)),
+            ));
+            self.alert_error = alerts::deliver(&alert, &self.alerts).err();
         }
     }
 
@@ -257,13 +752,31 @@ impl App {
     fn handle_app_event(&mut self, event: AppEvent) -> Result<()> {
         match event {
             AppEvent::Refresh(refresh_state) => self.refresh_state = refresh_state,
-            AppEvent::ScheduleLoad(schedules) => {
+            AppEvent::UpdateScheduled(update) => self.next_update = Some(update),
+            AppEvent::ScheduleLoad(entry) => {
+                // Stale entries may contain schedules that have already ended
+                let schedules = entry.value.without_ended(Utc::now());
+                self.schedules_cache_info = Some(entry.info);
                 if self.schedules != schedules {
-                    self.schedules = schedules.clone();
-                    CACHE_STORE.cache_set(format_option_string(&self.locale), schedules)?;
+                    self.schedules = schedules;
                     self.set_schedules_count()
                 }
             }
+            AppEvent::GearLoad(gear_shop) => {
+                self.gear_shop = *gear_shop;
+                self.gear_shop_error = None;
+            }
+            AppEvent::CoopRewardLoad(coop_reward) => {
+                self.coop_reward = Some(*coop_reward);
+                self.coop_reward_error = None;
+            }
+            AppEvent::FestivalsLoad(festivals) => {
+                self.festivals = *festivals;
+                self.festivals_error = None;
+            }
+            AppEvent::GearError(err) => self.gear_shop_error = Some(err),
+            AppEvent::CoopRewardError(err) => self.coop_reward_error = Some(err),
+            AppEvent::FestivalsError(err) => self.festivals_error = Some(err),
         }
 
         Ok(())
@@ -284,72 +797,153 @@ impl App {
     }
 
     fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<()> {
-        match mouse_event.kind {
-            crossterm::event::MouseEventKind::Down(MouseButton::Right) => {
-                self.handle_scroll(ScrollOperation::Reset);
-                Ok(())
-            }
-            crossterm::event::MouseEventKind::ScrollDown => {
-                self.handle_scroll(ScrollOperation::Down);
-                Ok(())
-            }
-            crossterm::event::MouseEventKind::ScrollUp => {
-                self.handle_scroll(ScrollOperation::Up);
-                Ok(())
-            }
-            _ => Ok(()),
+        if let Some(action) = self.keymap.mouse_action(mouse_event) {
+            self.handle_action(action);
         }
+        Ok(())
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<()> {
-        match key_event.modifiers {
-            event::KeyModifiers::CONTROL => {
-                if let event::KeyCode::Char(char) = key_event.code {
-                    match char {
-                        'l' => self.handle_scroll(ScrollOperation::Reset),
-                        'c' => self.quit(),
-                        _ => {}
-                    }
-                }
-            }
-            event::KeyModifiers::SHIFT => {
-                if key_event.code == event::KeyCode::BackTab {
-                    self.prev_tab();
+        if let Some(action) = self.keymap.key_action(key_event) {
+            self.handle_action(action);
+        }
+        Ok(())
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.quit(),
+            Action::Refresh => self.refresh(),
+            Action::NextTab => self.next_tab(),
+            Action::PrevTab => self.prev_tab(),
+            Action::Tab(n) => {
+                if let Some(tab) = self.tabs.get(n - 1) {
+                    self.app_ui.current_screen = *tab;
                 }
             }
-            event::KeyModifiers::NONE => match key_event.code {
-                event::KeyCode::Char(char) => match char {
-                    'q' => self.quit(),
-                    'r' => App::refresh_schedule(
-                        self.appevents_tx.clone(),
-                        self.locale.clone(),
-                        false,
-                    )?,
-                    'k' => self.handle_scroll(ScrollOperation::Up),
-                    'j' => self.handle_scroll(ScrollOperation::Down),
-                    'l' => self.next_tab(),
-                    'h' => self.prev_tab(),
-                    _ => {}
-                },
-                event::KeyCode::Esc => {
-                    self.quit();
-                }
-                event::KeyCode::Tab => {
-                    self.next_tab();
-                }
-                _ => {}
-            },
-            _ => {}
+            Action::ScrollUp => self.handle_scroll(ScrollOperation::Up),
+            Action::ScrollDown => self.handle_scroll(ScrollOperation::Down),
+            Action::PageUp => self.handle_scroll(ScrollOperation::PageUp),
+            Action::PageDown => self.handle_scroll(ScrollOperation::PageDown),
+            Action::ScrollTop => self.handle_scroll(ScrollOperation::Top),
+            Action::ScrollBottom => self.handle_scroll(ScrollOperation::Bottom),
+            Action::NextPanel => self.cycle_battle_panel(1),
+            Action::PrevPanel => self.cycle_battle_panel(BATTLE_PANELS.len() - 1),
+            Action::NextItem => self.cycle_selected_item(true),
+            Action::PrevItem => self.cycle_selected_item(false),
+            Action::Favorite => self.toggle_preference(Preference::Favorite),
+            Action::Dislike => self.toggle_preference(Preference::Disliked),
+            Action::Noop => {}
         }
-        Ok(())
+    }
+
+    fn refresh(&mut self) {
+        // The pending update is cancelled by the auto update task
+        self.next_update = None;
+        self.manual_refresh.notify_one();
     }
 
     fn next_tab(&mut self) {
-        self.app_ui.current_screen = self.app_ui.current_screen.next();
+        self.app_ui.current_screen = self.app_ui.current_screen.next(&self.tabs);
     }
 
     fn prev_tab(&mut self) {
-        self.app_ui.current_screen = self.app_ui.current_screen.prev();
+        self.app_ui.current_screen = self.app_ui.current_screen.prev(&self.tabs);
+    }
+
+    fn cycle_battle_panel(&mut self, step: usize) {
+        if self.app_ui.current_screen == AppScreen::Battles {
+            let panel = &mut self.app_ui.battles.panel;
+            *panel = (*panel + step) % BATTLE_PANELS.len();
+        }
+    }
+
+    /// Mark the selected stage or rule, and save how the preferences differ from the config file.
+    fn toggle_preference(&mut self, preference: Preference) {
+        let Some(id) = self.get_selected_entry().map(|entry| entry.id.clone()) else {
+            return;
+        };
+        self.preferences.toggle(&id, preference);
+        self.preferences_error = self
+            .preferences
+            .marks_since(&self.config_preferences)
+            .save(&self.preferences_path)
+            .err();
+    }
+
+    /// Select the next (or previous) rule or stage of the first rotation of the selected panel in
+    /// the battles tab. The other tabs have a single item per row.
+    fn cycle_selected_item(&mut self, forward: bool) {
+        let count = self.get_selectable_items().len();
+        if self.app_ui.current_screen != AppScreen::Battles || count == 0 {
+            return;
+        }
+        let item = &mut self.app_ui.battles.item;
+        let current = *item % count;
+        *item = if forward {
+            (current + 1) % count
+        } else {
+            (current + count - 1) % count
+        };
+    }
+
+    /// The rules and stages that can be selected: the rule and the stages of the first rotation
+    /// of the selected panel in the battles tab, the stage of the first shift in the work tab, or
+    /// the first stage in the stages tab.
+    fn get_selectable_items(&self) -> Vec<&NameID> {
+        let time_now = Utc::now();
+        let rotation = match self.app_ui.current_screen {
+            AppScreen::Battles => self
+                .schedules
+                .upcoming(self.get_battle_panel(), time_now)
+                .get(self.app_ui.battles.scroll_offset)
+                .copied(),
+            AppScreen::Work => self
+                .get_work_schedules(time_now)
+                .get(self.app_ui.work.scroll_offset)
+                .copied(),
+            AppScreen::Stages => {
+                return self
+                    .schedules
+                    .vs_stages
+                    .get(self.app_ui.stages.scroll_offset)
+                    .into_iter()
+                    .collect();
+            }
+            _ => None,
+        };
+        rotation
+            .map(|rotation| {
+                rotation
+                    .rule()
+                    .into_iter()
+                    .chain(rotation.stages())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// What `Action::Favorite` and `Dislike` apply to, one of `get_selectable_items`.
+    fn get_selected_entry(&self) -> Option<&NameID> {
+        let items = self.get_selectable_items();
+        let item = match self.app_ui.current_screen {
+            AppScreen::Battles => self.app_ui.battles.item,
+            _ => 0,
+        };
+        items.get(item % items.len().max(1)).copied()
+    }
+
+    fn get_battle_panel(&self) -> Mode {
+        BATTLE_PANELS[self.app_ui.battles.panel % BATTLE_PANELS.len()]
+    }
+
+    /// Salmon Run shifts of every kind that haven't ended at `time`. Big Runs and Team Contests
+    /// are announced ahead of time, sorting by start time places them among the regular shifts.
+    fn get_work_schedules(&self, time: DateTime<Utc>) -> Vec<ScheduleMatch<'_>> {
+        self.schedules.find(|rotation| {
+            matches!(rotation.mode, Mode::Work | Mode::BigRun | Mode::TeamContest)
+                && rotation.get_end_time() > time
+        })
     }
 
     fn handle_scroll(&mut self, operation: ScrollOperation) {
@@ -357,7 +951,8 @@ impl App {
             AppScreen::Battles => {
                 self.handle_battle_scrolling(operation);
             }
-            AppScreen::Work => { //TODO
+            AppScreen::Work => {
+                self.handle_work_scrolling(operation);
             }
             AppScreen::Challenges => {
                 //TODO
@@ -365,31 +960,45 @@ impl App {
             AppScreen::Fest => {
                 // TODO
             }
+            AppScreen::Stages => {
+                self.handle_stages_scrolling(operation);
+            }
+            AppScreen::Shop => {}
+            AppScreen::Festivals => {
+                self.handle_festivals_scrolling(operation);
+            }
         }
     }
 
+    fn handle_festivals_scrolling(&mut self, operation: ScrollOperation) {
+        let upper = self.get_region_festivals().len().saturating_sub(1);
+        let selected = &mut self.app_ui.festivals.selected;
+        *selected = operation.apply(*selected, upper);
+    }
+
+    fn get_region_festivals(&self) -> &[Festival] {
+        self.festivals
+            .get(&self.region)
+            .map(|festivals| &festivals[..])
+            .unwrap_or_default()
+    }
+
+    fn handle_work_scrolling(&mut self, operation: ScrollOperation) {
+        let upper = self.get_work_schedules(Utc::now()).len().saturating_sub(1);
+        let scroll_offset = &mut self.app_ui.work.scroll_offset;
+        *scroll_offset = operation.apply(*scroll_offset, upper);
+    }
+
+    fn handle_stages_scrolling(&mut self, operation: ScrollOperation) {
+        let upper = self.schedules.vs_stages.len().saturating_sub(1);
+        let scroll_offset = &mut self.app_ui.stages.scroll_offset;
+        *scroll_offset = operation.apply(*scroll_offset, upper);
+    }
+
     fn handle_battle_scrolling(&mut self, operation: ScrollOperation) {
-        match operation {
-            ScrollOperation::Up => {
-                self.app_ui.battles.scroll_offset = self
-                    .app_ui
-                    .battles
-                    .scroll_offset
-                    .saturating_sub(1)
-                    .clamp(0, self.get_battles_clamp_upper());
-            }
-            ScrollOperation::Down => {
-                self.app_ui.battles.scroll_offset = self
-                    .app_ui
-                    .battles
-                    .scroll_offset
-                    .saturating_add(1)
-                    .clamp(0, self.get_battles_clamp_upper());
-            }
-            ScrollOperation::Reset => {
-                self.app_ui.battles.scroll_offset = 0;
-            }
-        }
+        let upper = self.get_battles_clamp_upper();
+        let scroll_offset = &mut self.app_ui.battles.scroll_offset;
+        *scroll_offset = operation.apply(*scroll_offset, upper);
     }
 
     fn quit(&mut self) {
@@ -434,3 +1043,93 @@ impl App {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use idacast::cache::MIN_CACHE_LIFETIME;
+
+    use crate::config::RefreshPolicy;
+
+    use super::{
+        AppScreen, PAGE_LENGTH, RETRY_BASE_DELAY, RETRY_MAX_DELAY, ScrollOperation, cache_key,
+        get_auto_update_delay, get_retry_delay,
+    };
+
+    #[test]
+    fn test_cache_key() {
+        let lang = Some("zh-CN".to_string());
+        assert_eq!(cache_key(None, &None), "default");
+        assert_eq!(
+            cache_key(Some("https://splatoon3.ink/data/"), &lang),
+            "zh-CN"
+        );
+        assert_eq!(
+            cache_key(Some("resources/samples/bigrun"), &lang),
+            "zh-CN@resources/samples/bigrun"
+        );
+    }
+
+    #[test]
+    fn test_get_auto_update_delay() {
+        let time = Utc.with_ymd_and_hms(2025, 11, 8, 12, 0, 0).unwrap();
+        let policy = RefreshPolicy::default();
+        assert_eq!(
+            get_auto_update_delay(time + Duration::hours(5), time, &policy),
+            Duration::hours(5)
+        );
+        assert_eq!(
+            get_auto_update_delay(time - Duration::hours(5), time, &policy),
+            MIN_CACHE_LIFETIME
+        );
+
+        let policy = RefreshPolicy {
+            min_interval_secs: 3600,
+            ..Default::default()
+        };
+        assert_eq!(
+            get_auto_update_delay(time + Duration::minutes(5), time, &policy),
+            Duration::hours(1)
+        );
+    }
+
+    #[test]
+    fn test_get_retry_delay() {
+        let policy = RefreshPolicy::default();
+        assert_eq!(get_retry_delay(1, 1.0, &policy), RETRY_BASE_DELAY);
+        assert_eq!(get_retry_delay(1, 0.0, &policy), RETRY_BASE_DELAY / 2);
+        assert_eq!(get_retry_delay(3, 1.0, &policy), RETRY_BASE_DELAY * 4);
+        assert_eq!(get_retry_delay(3, 0.5, &policy), RETRY_BASE_DELAY * 3);
+
+        // Capped, even with a ridiculous number of attempts
+        assert_eq!(get_retry_delay(10, 1.0, &policy), RETRY_MAX_DELAY);
+        assert_eq!(get_retry_delay(u32::MAX, 1.0, &policy), RETRY_MAX_DELAY);
+        assert_eq!(get_retry_delay(u32::MAX, 0.0, &policy), RETRY_MAX_DELAY / 2);
+    }
+
+    #[test]
+    fn test_cycle_tabs() {
+        let tabs = [AppScreen::Work, AppScreen::Battles, AppScreen::Shop];
+        assert_eq!(AppScreen::Work.next(&tabs), AppScreen::Battles);
+        assert_eq!(AppScreen::Shop.next(&tabs), AppScreen::Work);
+        assert_eq!(AppScreen::Work.prev(&tabs), AppScreen::Shop);
+        assert_eq!(AppScreen::Battles.prev(&tabs), AppScreen::Work);
+        // Hidden tabs go back to the first visible one
+        assert_eq!(AppScreen::Stages.next(&tabs), AppScreen::Work);
+    }
+
+    #[test]
+    fn test_scroll_operation() {
+        assert_eq!(ScrollOperation::Up.apply(0, 10), 0);
+        assert_eq!(ScrollOperation::Down.apply(3, 10), 4);
+        assert_eq!(ScrollOperation::Down.apply(10, 10), 10);
+        assert_eq!(ScrollOperation::PageDown.apply(3, 10), 3 + PAGE_LENGTH);
+        assert_eq!(ScrollOperation::PageDown.apply(8, 10), 10);
+        assert_eq!(ScrollOperation::PageUp.apply(3, 10), 0);
+        assert_eq!(ScrollOperation::Top.apply(7, 10), 0);
+        assert_eq!(ScrollOperation::Bottom.apply(2, 10), 10);
+        // The upper bound shrinks when schedules end
+        assert_eq!(ScrollOperation::Up.apply(12, 10), 10);
+    }
+}
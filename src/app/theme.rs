@@ -0,0 +1,562 @@
+//! Colors of the TUI, by what they mean rather than where they're drawn. A built-in palette is
+//! picked in the `[theme]` section of the config file, where any of its colors can be replaced.
+use color_eyre::{Result, eyre::eyre};
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+
+use super::AppScreen;
+use crate::config::Preference;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    Display,
+    EnumString,
+    EnumIter,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub(crate) enum ThemeName {
+    /// For terminals with a dark background.
+    #[default]
+    Dark,
+    /// For terminals with a light background.
+    Light,
+    HighContrast,
+    /// No colors at all, only bold, dim and reversed text.
+    Monochrome,
+}
+
+/// How many colors the terminal can display. Colors of the theme are downsampled to the closest
+/// ones available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
+pub(crate) enum ColorDepth {
+    #[serde(rename = "none")]
+    #[strum(serialize = "none")]
+    NoColor,
+    #[serde(rename = "16")]
+    #[strum(serialize = "16")]
+    Ansi16,
+    #[serde(rename = "256")]
+    #[strum(serialize = "256")]
+    Ansi256,
+    #[serde(rename = "truecolor")]
+    #[strum(serialize = "truecolor")]
+    TrueColor,
+}
+
+/// The colors of every role, for a `ColorDepth`.
+///
+/// Accepts names ("lightgreen"), hex codes ("#2fde4e"), indexes of the 256 colors palette ("42")
+/// and "reset" for the default color of the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Theme {
+    /// The name of the program, in the header.
+    pub title: Color,
+    /// Secondary text, like the clock and the hints.
+    pub muted: Color,
+    pub tab_background: Color,
+    pub tabs: TabColors,
+    pub regular: Color,
+    pub anarchy: Color,
+    pub x_battle: Color,
+    pub challenge: Color,
+    pub fest: Color,
+    pub work: Color,
+    pub stages: Color,
+    pub shop: Color,
+    pub festivals: Color,
+    /// Background of the Big Run badge of Salmon Run shifts.
+    pub big_run: Color,
+    pub team_contest: Color,
+    pub unknown_rule: Color,
+    /// Text of the badges.
+    pub badge_text: Color,
+    /// Schedules that have ended, dimmed if "reset".
+    pub past: Color,
+    /// Favorite stages and rules, in bold.
+    pub favorite: Color,
+    /// Disliked stages and rules, dimmed if "reset".
+    pub disliked: Color,
+    pub error: Color,
+    pub warning: Color,
+    /// Notices, like being offline.
+    pub info: Color,
+    /// Something happening right now, like Tricolor battles.
+    pub live: Color,
+    #[serde(skip, default = "default_depth")]
+    depth: ColorDepth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct TabColors {
+    pub battles: Color,
+    pub work: Color,
+    pub challenges: Color,
+    pub fest: Color,
+    pub stages: Color,
+    pub shop: Color,
+    pub festivals: Color,
+}
+
+/// Colors of the 16 colors palette, as displayed by xterm.
+const ANSI_COLORS: [(Color, [u8; 3]); 16] = [
+    (Color::Black, [0, 0, 0]),
+    (Color::Red, [205, 0, 0]),
+    (Color::Green, [0, 205, 0]),
+    (Color::Yellow, [205, 205, 0]),
+    (Color::Blue, [0, 0, 238]),
+    (Color::Magenta, [205, 0, 205]),
+    (Color::Cyan, [0, 205, 205]),
+    (Color::Gray, [229, 229, 229]),
+    (Color::DarkGray, [127, 127, 127]),
+    (Color::LightRed, [255, 0, 0]),
+    (Color::LightGreen, [0, 255, 0]),
+    (Color::LightYellow, [255, 255, 0]),
+    (Color::LightBlue, [92, 92, 255]),
+    (Color::LightMagenta, [255, 0, 255]),
+    (Color::LightCyan, [0, 255, 255]),
+    (Color::White, [255, 255, 255]),
+];
+
+/// Levels of each channel in the 6x6x6 color cube of the 256 colors palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+fn default_depth() -> ColorDepth {
+    ColorDepth::TrueColor
+}
+
+impl ColorDepth {
+    /// Read from the environment: `NO_COLOR` (see <https://no-color.org/>), then `COLORTERM` and
+    /// `TERM`.
+    pub fn detect() -> Self {
+        ColorDepth::from_env(|name| std::env::var(name).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        if var("NO_COLOR").is_some_and(|value| !value.is_empty()) || term == "dumb" {
+            ColorDepth::NoColor
+        } else if var("COLORTERM").is_some_and(|value| value == "truecolor" || value == "24bit") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// The closest color that can be displayed.
+    pub fn downsample(self, color: Color) -> Color {
+        match (self, color) {
+            (ColorDepth::NoColor, _) => Color::Reset,
+            (ColorDepth::TrueColor, _) => color,
+            (ColorDepth::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(to_ansi256([r, g, b])),
+            (ColorDepth::Ansi256, _) => color,
+            (ColorDepth::Ansi16, Color::Rgb(r, g, b)) => to_ansi16([r, g, b]),
+            (ColorDepth::Ansi16, Color::Indexed(index)) => to_ansi16(indexed_to_rgb(index)),
+            (ColorDepth::Ansi16, _) => color,
+        }
+    }
+}
+
+fn distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| (i32::from(*a) - i32::from(b)).pow(2) as u32)
+        .sum()
+}
+
+fn to_ansi16(rgb: [u8; 3]) -> Color {
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|(_, ansi)| distance(rgb, *ansi))
+        .map_or(Color::Reset, |(color, _)| *color)
+}
+
+fn to_ansi256(rgb: [u8; 3]) -> u8 {
+    let cube_index = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|index| CUBE_LEVELS[*index].abs_diff(channel))
+            .unwrap_or_default() as u8
+    };
+    let [r, g, b] = rgb.map(cube_index);
+    let cube = 16 + 36 * r + 6 * g + b;
+
+    // The 24 grays, from 8 to 238
+    let average = (rgb.iter().map(|channel| u32::from(*channel)).sum::<u32>() / 3) as u8;
+    let gray = 232 + (average.saturating_sub(3) / 10).min(23);
+
+    if distance(rgb, indexed_to_rgb(gray)) < distance(rgb, indexed_to_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn indexed_to_rgb(index: u8) -> [u8; 3] {
+    match index {
+        0..16 => ANSI_COLORS[usize::from(index)].1,
+        16..232 => {
+            let index = index - 16;
+            [index / 36, (index / 6) % 6, index % 6].map(|level| CUBE_LEVELS[usize::from(level)])
+        }
+        232.. => [8 + 10 * (index - 232); 3],
+    }
+}
+
+impl Theme {
+    pub fn builtin(name: ThemeName) -> Self {
+        match name {
+            ThemeName::Dark => Theme {
+                title: Color::Green,
+                muted: Color::Gray,
+                tab_background: Color::Black,
+                tabs: TabColors {
+                    battles: Color::LightGreen,
+                    work: Color::LightRed,
+                    challenges: Color::LightMagenta,
+                    fest: Color::LightBlue,
+                    stages: Color::LightYellow,
+                    shop: Color::LightCyan,
+                    festivals: Color::Yellow,
+                },
+                regular: Color::Green,
+                anarchy: Color::Red,
+                x_battle: Color::Cyan,
+                challenge: Color::Magenta,
+                fest: Color::LightBlue,
+                work: Color::Red,
+                stages: Color::Yellow,
+                shop: Color::LightCyan,
+                festivals: Color::Yellow,
+                big_run: Color::Magenta,
+                team_contest: Color::Yellow,
+                unknown_rule: Color::Gray,
+                badge_text: Color::Black,
+                past: Color::Reset,
+                favorite: Color::LightYellow,
+                disliked: Color::Reset,
+                error: Color::Red,
+                warning: Color::Yellow,
+                info: Color::LightCyan,
+                live: Color::LightGreen,
+                depth: default_depth(),
+            },
+            ThemeName::Light => {
+                // Yellow is hard to read on a light background
+                let amber = Color::Rgb(0x9a, 0x67, 0x00);
+                let teal = Color::Rgb(0x00, 0x7a, 0x7a);
+                Theme {
+                    title: Color::Green,
+                    muted: Color::DarkGray,
+                    tab_background: Color::Reset,
+                    tabs: TabColors {
+                        battles: Color::Green,
+                        work: Color::Red,
+                        challenges: Color::Magenta,
+                        fest: Color::Blue,
+                        stages: amber,
+                        shop: teal,
+                        festivals: amber,
+                    },
+                    regular: Color::Green,
+                    anarchy: Color::Red,
+                    x_battle: teal,
+                    challenge: Color::Magenta,
+                    fest: Color::Blue,
+                    work: Color::Red,
+                    stages: amber,
+                    shop: teal,
+                    festivals: amber,
+                    big_run: Color::Magenta,
+                    team_contest: amber,
+                    unknown_rule: Color::DarkGray,
+                    badge_text: Color::White,
+                    past: Color::DarkGray,
+                    favorite: amber,
+                    disliked: Color::DarkGray,
+                    error: Color::Red,
+                    warning: amber,
+                    info: Color::Blue,
+                    live: Color::Green,
+                    depth: default_depth(),
+                }
+            }
+            ThemeName::HighContrast => Theme {
+                title: Color::LightGreen,
+                muted: Color::White,
+                tab_background: Color::Black,
+                tabs: TabColors {
+                    battles: Color::LightGreen,
+                    work: Color::LightRed,
+                    challenges: Color::LightMagenta,
+                    fest: Color::LightBlue,
+                    stages: Color::LightYellow,
+                    shop: Color::LightCyan,
+                    festivals: Color::LightYellow,
+                },
+                regular: Color::LightGreen,
+                anarchy: Color::LightRed,
+                x_battle: Color::LightCyan,
+                challenge: Color::LightMagenta,
+                fest: Color::LightBlue,
+                work: Color::LightRed,
+                stages: Color::LightYellow,
+                shop: Color::LightCyan,
+                festivals: Color::LightYellow,
+                big_run: Color::LightMagenta,
+                team_contest: Color::LightYellow,
+                unknown_rule: Color::White,
+                badge_text: Color::Black,
+                // Dimmed text is hard to read
+                past: Color::Gray,
+                favorite: Color::LightYellow,
+                disliked: Color::Gray,
+                error: Color::LightRed,
+                warning: Color::LightYellow,
+                info: Color::LightCyan,
+                live: Color::LightGreen,
+                depth: default_depth(),
+            },
+            ThemeName::Monochrome => {
+                Theme::builtin(ThemeName::Dark).downsampled(ColorDepth::NoColor)
+            }
+        }
+    }
+
+    /// The built-in theme `name`, with the colors of `overrides` (the same fields as `Theme`)
+    /// replaced, for the terminal's `depth`. The monochrome theme never has colors.
+    pub fn new(name: ThemeName, overrides: &toml::Table, depth: ColorDepth) -> Result<Self> {
+        let depth = match name {
+            ThemeName::Monochrome => ColorDepth::NoColor,
+            _ => depth,
+        };
+        let mut table = toml::Table::try_from(Theme::builtin(name))?;
+        merge_tables(&mut table, overrides);
+        let theme: Theme = table
+            .try_into()
+            .map_err(|err| eyre!("theme.colors: {err}"))?;
+        Ok(theme.downsampled(depth))
+    }
+
+    /// A color from the data, like the color of a Splatfest team, for the terminal.
+    pub fn color(&self, color: Color) -> Color {
+        self.depth.downsample(color)
+    }
+
+    /// Text on a `background`, reversed when there's no color.
+    pub fn badge(&self, background: Color) -> Style {
+        if background == Color::Reset {
+            Style::new().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::new().fg(self.badge_text).bg(background)
+        }
+    }
+
+    /// Schedules that have ended.
+    pub fn past(&self) -> Style {
+        if self.past == Color::Reset {
+            Style::new().add_modifier(Modifier::DIM)
+        } else {
+            Style::new().fg(self.past)
+        }
+    }
+
+    /// Stages and rules marked by the user, unchanged if they're not.
+    pub fn preference(&self, preference: Option<Preference>) -> Style {
+        match preference {
+            Some(Preference::Favorite) => {
+                Style::new().fg(self.favorite).add_modifier(Modifier::BOLD)
+            }
+            Some(Preference::Disliked) if self.disliked == Color::Reset => {
+                Style::new().add_modifier(Modifier::DIM)
+            }
+            Some(Preference::Disliked) => Style::new().fg(self.disliked),
+            None => Style::new(),
+        }
+    }
+
+    fn downsampled(self, depth: ColorDepth) -> Self {
+        let color = |color| depth.downsample(color);
+        Theme {
+            title: color(self.title),
+            muted: color(self.muted),
+            tab_background: color(self.tab_background),
+            tabs: TabColors {
+                battles: color(self.tabs.battles),
+                work: color(self.tabs.work),
+                challenges: color(self.tabs.challenges),
+                fest: color(self.tabs.fest),
+                stages: color(self.tabs.stages),
+                shop: color(self.tabs.shop),
+                festivals: color(self.tabs.festivals),
+            },
+            regular: color(self.regular),
+            anarchy: color(self.anarchy),
+            x_battle: color(self.x_battle),
+            challenge: color(self.challenge),
+            fest: color(self.fest),
+            work: color(self.work),
+            stages: color(self.stages),
+            shop: color(self.shop),
+            festivals: color(self.festivals),
+            big_run: color(self.big_run),
+            team_contest: color(self.team_contest),
+            unknown_rule: color(self.unknown_rule),
+            badge_text: color(self.badge_text),
+            past: color(self.past),
+            favorite: color(self.favorite),
+            disliked: color(self.disliked),
+            error: color(self.error),
+            warning: color(self.warning),
+            info: color(self.info),
+            live: color(self.live),
+            depth,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::builtin(ThemeName::default())
+    }
+}
+
+/// Replace the values of `table` with the ones of `overrides`, recursively for the sub-tables.
+fn merge_tables(table: &mut toml::Table, overrides: &toml::Table) {
+    for (key, value) in overrides {
+        match (table.get_mut(key), value) {
+            (Some(toml::Value::Table(table)), toml::Value::Table(overrides)) => {
+                merge_tables(table, overrides)
+            }
+            _ => {
+                table.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+impl TabColors {
+    pub fn get(&self, screen: AppScreen) -> Color {
+        match screen {
+            AppScreen::Battles => self.battles,
+            AppScreen::Work => self.work,
+            AppScreen::Challenges => self.challenges,
+            AppScreen::Fest => self.fest,
+            AppScreen::Stages => self.stages,
+            AppScreen::Shop => self.shop,
+            AppScreen::Festivals => self.festivals,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use ratatui::style::{Color, Modifier, Style};
+    use strum::IntoEnumIterator;
+
+    use crate::{app::AppScreen, config::Preference};
+
+    use super::{ColorDepth, Theme, ThemeName, to_ansi16, to_ansi256};
+
+    #[test]
+    fn test_detect_color_depth() {
+        let detect = |vars: &[(&str, &str)]| {
+            let vars: HashMap<&str, &str> = vars.iter().copied().collect();
+            ColorDepth::from_env(|name| vars.get(name).map(|value| value.to_string()))
+        };
+        assert_eq!(detect(&[]), ColorDepth::Ansi16);
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), ColorDepth::Ansi256);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(
+            detect(&[("COLORTERM", "truecolor"), ("NO_COLOR", "1")]),
+            ColorDepth::NoColor
+        );
+        // An empty NO_COLOR doesn't count
+        assert_eq!(
+            detect(&[("COLORTERM", "24bit"), ("NO_COLOR", "")]),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorDepth::NoColor);
+    }
+
+    #[test]
+    fn test_downsample() {
+        assert_eq!(to_ansi256([255, 0, 0]), 196);
+        assert_eq!(to_ansi256([0x9a, 0x67, 0x00]), 94);
+        assert_eq!(to_ansi256([128, 128, 128]), 244);
+        assert_eq!(to_ansi16([250, 10, 10]), Color::LightRed);
+        assert_eq!(to_ansi16([0x00, 0x7a, 0x7a]), Color::Cyan);
+        assert_eq!(
+            ColorDepth::Ansi16.downsample(Color::Indexed(196)),
+            Color::LightRed
+        );
+        assert_eq!(ColorDepth::Ansi256.downsample(Color::Cyan), Color::Cyan);
+        assert_eq!(ColorDepth::NoColor.downsample(Color::Cyan), Color::Reset);
+    }
+
+    #[test]
+    fn test_builtin_themes() {
+        for name in ThemeName::iter() {
+            let theme = Theme::builtin(name);
+            // Every tab is visible in the header
+            assert!(
+                AppScreen::iter().all(|tab| theme.tabs.get(tab) != theme.tab_background
+                    || name == ThemeName::Monochrome)
+            );
+        }
+
+        let monochrome = Theme::builtin(ThemeName::Monochrome);
+        assert_eq!(monochrome.anarchy, Color::Reset);
+        assert_eq!(
+            monochrome.badge(monochrome.big_run),
+            Style::new().add_modifier(Modifier::REVERSED)
+        );
+        assert_eq!(monochrome.past(), Style::new().add_modifier(Modifier::DIM));
+        assert_eq!(
+            monochrome.preference(Some(Preference::Favorite)),
+            Style::new().fg(Color::Reset).add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(
+            monochrome.preference(Some(Preference::Disliked)),
+            Style::new().add_modifier(Modifier::DIM)
+        );
+
+        let light = Theme::new(ThemeName::Light, &toml::Table::new(), ColorDepth::Ansi16).unwrap();
+        assert!(light.stages != Color::Reset && !matches!(light.stages, Color::Rgb(..)));
+        assert_eq!(light.color(Color::Rgb(0, 0, 250)), Color::Blue);
+    }
+
+    #[test]
+    fn test_theme_overrides() {
+        let overrides: toml::Table =
+            toml::from_str("x_battle = \"#2fde4e\"\ntabs.work = \"red\"").unwrap();
+        let theme = Theme::new(ThemeName::Dark, &overrides, ColorDepth::TrueColor).unwrap();
+        assert_eq!(theme.x_battle, Color::Rgb(0x2f, 0xde, 0x4e));
+        assert_eq!(theme.tabs.work, Color::Red);
+        assert_eq!(theme.tabs.battles, Color::LightGreen);
+
+        let theme = Theme::new(ThemeName::Dark, &overrides, ColorDepth::Ansi256).unwrap();
+        assert!(matches!(theme.x_battle, Color::Indexed(_)));
+
+        let typo: toml::Table = toml::from_str("x_batle = \"red\"").unwrap();
+        assert!(Theme::new(ThemeName::Dark, &typo, ColorDepth::TrueColor).is_err());
+        let invalid: toml::Table = toml::from_str("anarchy = \"not a color\"").unwrap();
+        assert!(Theme::new(ThemeName::Dark, &invalid, ColorDepth::TrueColor).is_err());
+    }
+}
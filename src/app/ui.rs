@@ -1,18 +1,22 @@
 use std::cmp::{max, min};
 
 use crate::{
-    app::{App, AppScreen, RefreshState},
-    data::{
-        filter_schedules,
-        schedules::{BattleSchedule, CoopRule, CoopSchedule, LeagueSchedule},
-    },
+    app::{App, AppScreen, RefreshState, keymap::Action, theme::Theme},
+    config::Preferences,
 };
 use chrono::{DateTime, Duration, Local, SubsecRound, TimeDelta, Utc};
+use color_eyre::Report;
+use idacast::data::{
+    DataError,
+    festivals::Festival,
+    gear::GearSale,
+    query::{Mode, Rotation, ScheduleMatch},
+    schedules::{CoopRule, CurrentFest, FestState, LeagueSchedule, NameID, Schedule},
+};
 use ratatui::{
     prelude::*,
-    widgets::{Block, Paragraph, Tabs, Wrap},
+    widgets::{Block, BorderType, List, ListState, Paragraph, Row, Table, TableState, Tabs, Wrap},
 };
-use strum::IntoEnumIterator;
 use unicode_width::UnicodeWidthStr;
 
 use super::AppUI;
@@ -33,11 +37,34 @@ pub fn draw(app: &App, frame: &mut Frame) {
 
     render_footer(app, frame, footer_area);
 
+    let content_area = match &app.refresh_state {
+        RefreshState::Error(report) => {
+            let [content_area, error_area] =
+                Layout::vertical([Constraint::Min(5), Constraint::Length(5)]).areas(content_area);
+            render_error_panel(app, report, frame, error_area);
+            content_area
+        }
+        _ => content_area,
+    };
+
+    let content_area = if app.schedules.warnings.is_empty() {
+        content_area
+    } else {
+        let height = app.schedules.warnings.len().min(MAX_WARNING_LINES + 1) as u16 + 2;
+        let [content_area, warning_area] =
+            Layout::vertical([Constraint::Min(5), Constraint::Length(height)]).areas(content_area);
+        render_warning_panel(&app.schedules.warnings, &app.theme, frame, warning_area);
+        content_area
+    };
+
     match app.app_ui.current_screen {
         AppScreen::Battles => render_battle_stages(app, frame, content_area),
         AppScreen::Work => render_work(app, frame, content_area),
         AppScreen::Challenges => render_challenges(app, frame, content_area),
         AppScreen::Fest => render_splatfest(app, frame, content_area),
+        AppScreen::Stages => render_stages(app, frame, content_area),
+        AppScreen::Shop => render_shop(app, frame, content_area),
+        AppScreen::Festivals => render_festivals(app, frame, content_area),
     }
 }
 
@@ -45,13 +72,17 @@ fn render_header(app: &App, frame: &mut Frame<'_>, header_area: Rect) {
     let time = Local::now()
         .format("%H:%M:%S <%a>")
         .to_string()
-        .fg(Color::Gray);
-    let title = "IdaCast".bold().fg(Color::Green);
+        .fg(app.theme.muted);
+    let title = "IdaCast".bold().fg(app.theme.title);
 
-    let tab_titles = AppScreen::iter().map(AppScreen::to_tab_title);
+    let tab_titles = app.tabs.iter().map(|tab| tab.to_tab_title(&app.theme));
     let tabs = Tabs::new(tab_titles)
         .highlight_style(Modifier::REVERSED | Modifier::BOLD)
-        .select(app.app_ui.current_screen as usize)
+        .select(
+            app.tabs
+                .iter()
+                .position(|tab| *tab == app.app_ui.current_screen),
+        )
         .divider(" ")
         .padding("", "");
 
@@ -76,37 +107,99 @@ fn get_scroll_offset(cur_screen: &AppScreen, app_ui: &AppUI) -> usize {
         AppScreen::Work => app_ui.work.scroll_offset,
         AppScreen::Challenges => app_ui.challenges.scroll_offset,
         AppScreen::Fest => app_ui.fest.scroll_offset,
+        AppScreen::Stages => app_ui.stages.scroll_offset,
+        AppScreen::Shop => 0,
+        AppScreen::Festivals => app_ui.festivals.selected,
     }
 }
 
 fn render_footer(app: &App, frame: &mut Frame<'_>, footer_area: Rect) {
     let scroll_offset = get_scroll_offset(&app.app_ui.current_screen, &app.app_ui);
-    let battle_schedules_count = app.app_ui.battles.schedules_count;
+    let scroll_count = match app.app_ui.current_screen {
+        AppScreen::Stages => app.schedules.vs_stages.len(),
+        AppScreen::Festivals => app.get_region_festivals().len(),
+        AppScreen::Work => app.get_work_schedules(Utc::now()).len(),
+        _ => app
+            .app_ui
+            .battles
+            .schedules_count
+            .saturating_sub(app.get_past_schedule_count()),
+    };
 
-    let scroll_info = if scroll_offset == 0 || battle_schedules_count == 0 {
-        "(j/k to scroll)".to_string()
+    let hint = |action| app.keymap.hint(action).unwrap_or_default();
+    let scroll_info = if scroll_offset == 0 || scroll_count == 0 {
+        format!(
+            "({}/{} to scroll)",
+            hint(Action::ScrollDown),
+            hint(Action::ScrollUp)
+        )
     } else {
         format!(
-            "(^L to reset scroll) lines {}/{}",
+            "({} to reset scroll) lines {}/{}",
+            hint(Action::ScrollTop),
             scroll_offset.saturating_add(1),
-            battle_schedules_count.saturating_sub(app.get_past_schedule_count()),
+            scroll_count,
         )
     }
     .italic()
-    .fg(Color::Gray);
-    let status = match &app.refresh_state {
-        RefreshState::Pending => Span::from("Updating..."),
-        RefreshState::Completed(time, cached) => {
-            Span::from(format!("Last updated: {}{}", time.format("%H:%M:%S"), {
-                if *cached { " (cached)" } else { "" }
-            }))
+    .fg(app.theme.muted);
+    let mut status = Line::from(
+        match &app.refresh_state {
+            RefreshState::Pending => Span::from("Updating..."),
+            RefreshState::Completed(time, cached) => {
+                Span::from(format!("Last updated: {}{}", time.format("%H:%M:%S"), {
+                    if *cached { " (cached)" } else { "" }
+                }))
+            }
+            // Details are shown in the error panel
+            RefreshState::Error(_) => Span::from("Failed to update"),
         }
-        RefreshState::Error(report) => Span::from(format!("Failed to update: {report}")),
+        .fg(app.theme.muted),
+    );
+    if let Some(cache_info) = app
+        .schedules_cache_info
+        .filter(|cache_info| cache_info.is_stale(Utc::now()))
+    {
+        status.push_span(
+            format!(
+                " [stale: fetched {} ago]",
+                format_elapsed(cache_info.fetched_at)
+            )
+            .fg(app.theme.warning),
+        );
+    }
+    if let Some(next_update) = app
+        .next_update
+        .filter(|next_update| next_update.failed_attempts > 0)
+    {
+        status.push_span(
+            format!(
+                " [attempt #{} at {}]",
+                next_update.failed_attempts.saturating_add(1),
+                DateTime::<Local>::from(next_update.time).format("%H:%M:%S")
+            )
+            .fg(app.theme.warning),
+        );
+    }
+    if app.source.is_none() {
+        status.push_span(" [offline]".fg(app.theme.info));
+    }
+    if let Some((_, alert)) = app
+        .last_alert
+        .as_ref()
+        .filter(|(start_time, _)| *start_time > Utc::now())
+    {
+        status.push_span(format!(" [{alert}]").fg(app.theme.live));
+    }
+    if let Some(err) = &app.alert_error {
+        status.push_span(format!(" [alert not delivered: {err}]").fg(app.theme.error));
+    }
+    if let Some(err) = &app.preferences_error {
+        status.push_span(format!(" [preferences not saved: {err}]").fg(app.theme.error));
     }
-    .fg(Color::Gray);
 
     let [status_area, _spacer, scroll_info_area] = Layout::horizontal([
-        Constraint::Length(status.content.len() as u16),
+        Constraint::Length(status.width() as u16),
         Constraint::Fill(1),
         Constraint::Length(scroll_info.content.len() as u16),
     ])
@@ -118,6 +211,93 @@ fn render_footer(app: &App, frame: &mut Frame<'_>, footer_area: Rect) {
     frame.render_widget(scroll_info, scroll_info_area);
 }
 
+fn render_error_panel(app: &App, report: &Report, frame: &mut Frame<'_>, area: Rect) {
+    let block = Block::bordered()
+        .border_style(Style::new().fg(app.theme.error))
+        .title(" Failed to update ".bold().fg(app.theme.error));
+    // Refresh may be unbound
+    let hint = app.keymap.hint(Action::Refresh);
+    let retry = match (app.next_update, hint) {
+        (Some(next_update), hint) if next_update.time > Utc::now() => {
+            let countdown = format_countdown(next_update.time);
+            match hint {
+                Some(key) => format!("Retrying in {countdown} (press {key} to retry now)"),
+                None => format!("Retrying in {countdown}"),
+            }
+        }
+        (_, Some(key)) => format!("Press {key} to retry"),
+        (_, None) => String::new(),
+    };
+    let content = vec![
+        Line::from(report.to_string()),
+        Line::from(suggest_action(report).italic()),
+        Line::from(retry.fg(app.theme.muted)),
+    ];
+    frame.render_widget(
+        Paragraph::new(content)
+            .block(block)
+            .wrap(Wrap { trim: true }),
+        area,
+    );
+}
+
+/// Number of warnings listed in the partial data panel, the others are summarized.
+const MAX_WARNING_LINES: usize = 3;
+
+fn render_warning_panel(warnings: &[String], theme: &Theme, frame: &mut Frame<'_>, area: Rect) {
+    let block = Block::bordered()
+        .border_style(Style::new().fg(theme.warning))
+        .title(" Partial data ".bold().fg(theme.warning))
+        .title_bottom(
+            " The data format may have changed, check for a newer version of idacast "
+                .italic()
+                .fg(theme.muted),
+        );
+    // Listing the last warning takes as much room as summarizing it
+    let shown = if warnings.len() > MAX_WARNING_LINES + 1 {
+        MAX_WARNING_LINES
+    } else {
+        warnings.len()
+    };
+    let mut content: Vec<Line> = warnings[..shown]
+        .iter()
+        .map(|warning| Line::from(warning.as_str()))
+        .collect();
+    if shown < warnings.len() {
+        content.push(Line::from(
+            format!("and {} more", warnings.len() - shown).fg(theme.muted),
+        ));
+    }
+    frame.render_widget(Paragraph::new(content).block(block), area);
+}
+
+/// What the user can do about an error.
+fn suggest_action(report: &Report) -> &'static str {
+    match report.downcast_ref::<DataError>() {
+        Some(DataError::NetworkUnreachable { .. }) => {
+            "Check your network connection, or use --offline to browse the cached data."
+        }
+        Some(DataError::Timeout { .. }) => "The server is slow to respond, try again later.",
+        Some(DataError::HttpStatus { status, .. }) if *status >= 500 => {
+            "The server is having issues, try again later."
+        }
+        Some(DataError::HttpStatus { .. }) => "Check the URL given to --source.",
+        Some(
+            DataError::SchemaMismatch { .. }
+            | DataError::ObjectNonExist(_)
+            | DataError::TranslationError(_),
+        ) => "The data format may have changed, check for a newer version of idacast.",
+        Some(DataError::CacheCorruption { .. }) => "Run idacast with --clear-cache.",
+        Some(DataError::CacheUnavailable { .. }) => {
+            "Check that idacast isn't already running, or use --clear-cache."
+        }
+        Some(DataError::UnsupportedLocale(_)) => {
+            "Use --language to pick a supported language, e.g. en-US."
+        }
+        None => "Try again later.",
+    }
+}
+
 fn render_battle_stages(app: &App, frame: &mut Frame<'_>, stage_area: Rect) {
     let [bankara_area, battle_area] =
         Layout::vertical([Constraint::Min(5), Constraint::Min(5)]).areas(stage_area);
@@ -131,29 +311,44 @@ fn render_battle_stages(app: &App, frame: &mut Frame<'_>, stage_area: Rect) {
     let display_count: usize = anarchy_series_area.height as usize / 3;
     // Assuming every block
     // have the same size
+    let time_now = Utc::now();
 
-    let filtered_open = filter_schedules(
-        &app.schedules.anarchy_open,
-        display_count,
-        Some(app.app_ui.battles.scroll_offset),
-    );
-    let filtered_series = filter_schedules(
-        &app.schedules.anarchy_series,
-        display_count,
-        Some(app.app_ui.battles.scroll_offset),
-    );
-    let anarchy_open_block = Block::bordered()
-        .border_style(Style::new().red())
-        .title("Anarchy Open");
-    let anarchy_series_block = Block::bordered()
-        .border_style(Style::new().red())
-        .title("Anarchy Series");
-
-    render_schedule_widget(filtered_open, anarchy_open_area, anarchy_open_block, frame);
-    render_schedule_widget(
-        filtered_series,
+    let render_panel = |mode: Mode, color: Color, area: Rect, frame: &mut Frame| {
+        let mut block = Block::bordered()
+            .border_style(Style::new().fg(color))
+            .title(mode.name());
+        let mut selected = None;
+        if mode == app.get_battle_panel() {
+            block = block.border_type(BorderType::Thick);
+            selected = app.get_selected_entry();
+        }
+        let schedules: Vec<ScheduleMatch> = app
+            .schedules
+            .upcoming(mode, time_now)
+            .into_iter()
+            .skip(app.app_ui.battles.scroll_offset)
+            .take(display_count)
+            .collect();
+        render_schedule_widget(
+            &schedules,
+            selected,
+            area,
+            block,
+            &app.preferences,
+            &app.theme,
+            frame,
+        );
+    };
+    render_panel(
+        Mode::AnarchySeries,
+        app.theme.anarchy,
         anarchy_series_area,
-        anarchy_series_block,
+        frame,
+    );
+    render_panel(
+        Mode::AnarchyOpen,
+        app.theme.anarchy,
+        anarchy_open_area,
         frame,
     );
 
@@ -163,33 +358,8 @@ fn render_battle_stages(app: &App, frame: &mut Frame<'_>, stage_area: Rect) {
         .flex(layout::Flex::SpaceAround)
         .spacing(1)
         .areas(battle_area);
-    let x_battle_block = Block::bordered()
-        .border_style(Style::new().cyan())
-        .title("X Battle");
-    let regular_battle_block = Block::bordered()
-        .border_style(Style::new().green())
-        .title("Regular Battle");
-
-    render_schedule_widget(
-        filter_schedules(
-            &app.schedules.x_battle,
-            display_count,
-            Some(app.app_ui.battles.scroll_offset),
-        ),
-        x_battle_area,
-        x_battle_block,
-        frame,
-    );
-    render_schedule_widget(
-        filter_schedules(
-            &app.schedules.regular,
-            display_count,
-            Some(app.app_ui.battles.scroll_offset),
-        ),
-        regular_area,
-        regular_battle_block,
-        frame,
-    );
+    render_panel(Mode::XBattle, app.theme.x_battle, x_battle_area, frame);
+    render_panel(Mode::Regular, app.theme.regular, regular_area, frame);
 }
 
 fn center_single_block(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
@@ -208,109 +378,157 @@ fn render_work(app: &App, frame: &mut Frame, area: Rect) {
         Constraint::Max((area.width as f64 * 0.8).floor() as u16),
         Constraint::Max((area.height as f64 * 0.95).floor() as u16),
     );
-    let block = Block::bordered()
-        .border_style(Color::Red)
+    let mut block = Block::bordered()
+        .border_style(Style::new().fg(app.theme.work))
         .title("Grizzco Work");
-    let sorted_work_schedules = &mut [
-        &app.schedules.work_regular[..],
-        &app.schedules.work_big_run[..],
-        &app.schedules.work_team_contest[..],
-    ]
-    .concat();
-    sorted_work_schedules.sort_by(|l, r| l.end_time.cmp(&r.end_time)); // The special work may be
-    // announced ahead of time, and don't know where it fits in the regular work schedule
+    if let Some(coop_reward) = &app.coop_reward {
+        block = block.title(
+            Line::from(vec![
+                "Monthly Reward: ".into(),
+                coop_reward.monthly_gear.name.clone().bold(),
+            ])
+            .right_aligned(),
+        );
+    } else if app.coop_reward_error.is_some() {
+        block = block
+            .title(Line::from("Monthly Reward: unavailable".fg(app.theme.muted)).right_aligned());
+    }
+    let schedules: Vec<ScheduleMatch> = app
+        .get_work_schedules(Utc::now())
+        .into_iter()
+        .skip(app.app_ui.work.scroll_offset)
+        .take(area.height as usize / 3)
+        .collect();
 
     render_work_widget(
-        filter_schedules(
-            sorted_work_schedules,
-            area.height as usize / 3,
-            Some(app.app_ui.work.scroll_offset),
-        ),
+        &schedules,
+        app.get_selected_entry(),
         area,
         block,
+        &app.preferences,
+        &app.theme,
         frame,
     );
 }
 
+/// Favorite stages are highlighted, and disliked ones dimmed. The `selected` stage of the first
+/// shift is reversed.
 fn render_work_widget(
-    schedules: Option<&[CoopSchedule]>,
+    schedules: &[ScheduleMatch],
+    selected: Option<&NameID>,
     area: Rect,
     block: Block,
+    preferences: &Preferences,
+    theme: &Theme,
     frame: &mut Frame,
 ) {
     let sub_area = block.inner(area);
-    let content = match schedules {
-        Some(schedules) => {
-            let mut text: Vec<Line> = Vec::new();
-
-            for schedule in schedules {
-                let line = format_work_schedule_title(
-                    sub_area,
-                    schedule.stage.name.clone(),
-                    &schedule.rule,
-                    schedule.start_time,
-                    schedule.end_time,
-                );
-                text.push(line);
-                let boss = match &schedule.boss {
-                    Some(boss) => boss.name.clone().bold(),
-                    None => "".bold(),
-                };
-                let weapons = schedule
-                    .weapons
-                    .iter()
-                    .map(|weapon| weapon.name.clone())
-                    .collect::<Vec<String>>()
-                    .join(" / ");
-                let mid_space = fill_mid_spaces(&boss.content, &weapons, sub_area);
-                text.push(Line::from(vec![
-                    weapons.italic(),
-                    mid_space.into(),
-                    boss.bold(),
-                ]));
-                text.push(Line::from(""));
-            }
+    let content = if schedules.is_empty() {
+        Paragraph::new("Loading...")
+    } else {
+        let mut text: Vec<Line> = Vec::new();
 
-            Paragraph::new(text)
+        for (index, rotation) in schedules.iter().enumerate() {
+            let Rotation::Work(schedule) = rotation.rotation else {
+                continue;
+            };
+            let line = format_work_schedule_title(
+                theme,
+                sub_area,
+                schedule.stage.name.clone(),
+                entry_style(
+                    theme,
+                    preferences,
+                    &schedule.stage,
+                    index == 0 && selected == Some(&schedule.stage),
+                ),
+                &schedule.rule,
+                schedule.start_time,
+                schedule.end_time,
+            );
+            text.push(line);
+            let boss = match &schedule.boss {
+                Some(boss) => boss.name.clone().bold(),
+                None => "".bold(),
+            };
+            let weapons = schedule
+                .weapons
+                .iter()
+                .map(|weapon| weapon.name.clone())
+                .collect::<Vec<String>>()
+                .join(" / ");
+            let mid_space = fill_mid_spaces(&boss.content, &weapons, sub_area);
+            text.push(Line::from(vec![
+                weapons.italic(),
+                mid_space.into(),
+                boss.bold(),
+            ]));
+            text.push(Line::from(""));
         }
-        None => Paragraph::new("Loading..."),
+
+        Paragraph::new(text)
     };
 
     frame.render_widget(content.block(block), area);
 }
 
+/// Favorite stages and rules are highlighted, and disliked ones dimmed. The `selected` rule or
+/// stage of the first schedule is reversed.
 fn render_schedule_widget(
-    schedules: Option<&[BattleSchedule]>,
+    schedules: &[ScheduleMatch],
+    selected: Option<&NameID>,
     area: Rect,
     block: Block,
+    preferences: &Preferences,
+    theme: &Theme,
     frame: &mut Frame,
 ) {
     let sub_area = block.inner(area);
-    let content = match schedules {
-        Some(schedules) => {
-            let mut text: Vec<Line> = Vec::new();
-            for schedule in schedules {
-                let line = format_schedule_title(
-                    sub_area,
-                    schedule.rule.name.clone(),
-                    schedule.start_time,
-                    schedule.end_time,
-                );
-                text.push(line);
-                for stage in &schedule.stages {
-                    text.push(format!("- {}", stage.name).into());
-                }
+    let content = if schedules.is_empty() {
+        Paragraph::new("Loading...")
+    } else {
+        let mut text: Vec<Line> = Vec::new();
+        for (index, schedule) in schedules.iter().enumerate() {
+            let Some(rule) = schedule.rule() else {
+                continue;
+            };
+            let is_selected = |entry| index == 0 && selected == Some(entry);
+            let line = format_schedule_title(
+                sub_area,
+                rule.name.clone(),
+                entry_style(theme, preferences, rule, is_selected(rule)),
+                schedule.get_start_time(),
+                schedule.get_end_time(),
+            );
+            text.push(line);
+            for stage in schedule.stages() {
+                text.push(Line::from(format!("- {}", stage.name)).style(entry_style(
+                    theme,
+                    preferences,
+                    stage,
+                    is_selected(stage),
+                )));
             }
-            Paragraph::new(text)
         }
-        None => Paragraph::new("Loading..."),
+        Paragraph::new(text)
     };
     frame.render_widget(content.block(block), area);
 }
 
+/// The style of a stage or rule, from the preference of the user, reversed if it's `selected`.
+fn entry_style(theme: &Theme, preferences: &Preferences, entry: &NameID, selected: bool) -> Style {
+    let style = theme.preference(preferences.get(&entry.id));
+    if selected {
+        style.add_modifier(Modifier::REVERSED)
+    } else {
+        style
+    }
+}
+
 fn render_challenges(app: &App, frame: &mut Frame, area: Rect) {
     if app.schedules.league.is_empty() {
         render_error_widget(
+            &app.theme,
             frame,
             area,
             "No Data.",
@@ -330,19 +548,24 @@ fn render_challenges(app: &App, frame: &mut Frame, area: Rect) {
     };
 
     for (index, challenge) in app.schedules.league.iter().enumerate() {
-        render_challenge_widget(challenge, divided_areas[index], frame);
+        render_challenge_widget(challenge, divided_areas[index], &app.theme, frame);
     }
 }
 
 const HTML_NEW_LINE: &str = "<br />";
 
-fn render_challenge_widget(challenge_event: &LeagueSchedule, area: Rect, frame: &mut Frame) {
+fn render_challenge_widget(
+    challenge_event: &LeagueSchedule,
+    area: Rect,
+    theme: &Theme,
+    frame: &mut Frame,
+) {
     let block = Block::bordered()
         .title(Line::from(challenge_event.event_name.name.clone()).centered())
         .title_bottom(
             Line::from(challenge_event.desc.clone().replace(HTML_NEW_LINE, " ")).right_aligned(),
         )
-        .border_style(Style::new().magenta());
+        .border_style(Style::new().fg(theme.challenge));
 
     let mut content: Vec<Line> = Vec::new();
 
@@ -374,7 +597,7 @@ fn render_challenge_widget(challenge_event: &LeagueSchedule, area: Rect, frame:
         .centered();
         content.push(if time_period.end_time < now {
             // Past schedules
-            line.dim().italic()
+            line.patch_style(theme.past()).italic()
         } else {
             line
         });
@@ -394,18 +617,419 @@ fn render_challenge_widget(challenge_event: &LeagueSchedule, area: Rect, frame:
     );
 }
 
-fn render_splatfest(_app: &App, frame: &mut Frame, area: Rect) {
-    render_error_widget(
+fn render_splatfest(app: &App, frame: &mut Frame, area: Rect) {
+    if app.schedules.current_fest.is_none() && app.schedules.fest_open.is_empty() {
+        render_error_widget(
+            &app.theme,
+            frame,
+            area,
+            "No Data.",
+            "Either the program is loading, or there isn't a Splatfest announced at the moment.",
+        );
+        return;
+    }
+
+    let [fest_info_area, fest_battle_area] =
+        Layout::vertical([Constraint::Length(5), Constraint::Min(5)])
+            .spacing(1)
+            .areas(area);
+
+    if let Some(fest) = &app.schedules.current_fest {
+        match &fest.tricolor_stage {
+            Some(tricolor_stage) => {
+                let [info_area, tricolor_area] =
+                    Layout::horizontal([Constraint::Fill(2), Constraint::Fill(1)])
+                        .flex(layout::Flex::SpaceAround)
+                        .spacing(1)
+                        .areas(fest_info_area);
+                render_fest_info_widget(fest, info_area, &app.theme, frame);
+                render_tricolor_widget(
+                    fest,
+                    &tricolor_stage.name,
+                    tricolor_area,
+                    &app.theme,
+                    frame,
+                );
+            }
+            None => render_fest_info_widget(fest, fest_info_area, &app.theme, frame),
+        }
+    }
+
+    let [fest_pro_area, fest_open_area] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Fill(1), Constraint::Fill(1)])
+        .flex(layout::Flex::SpaceAround)
+        .spacing(1)
+        .areas(fest_battle_area);
+    let display_count: usize = fest_open_area.height as usize / 3;
+
+    let fest_open_block = Block::bordered()
+        .border_style(Style::new().fg(app.theme.fest))
+        .title("Splatfest Open");
+    let fest_pro_block = Block::bordered()
+        .border_style(Style::new().fg(app.theme.fest))
+        .title("Splatfest Pro");
+
+    let fest_open = app.schedules.next_n(Mode::FestOpen, display_count);
+    let fest_pro = app.schedules.next_n(Mode::FestPro, display_count);
+    render_schedule_widget(
+        &fest_open,
+        None,
+        fest_open_area,
+        fest_open_block,
+        &app.preferences,
+        &app.theme,
         frame,
-        area,
-        "Not Implemented!",
-        "This page hasn't been implemented yet. See \n https://github.com/rywng/idacast/issues/9",
     );
+    render_schedule_widget(
+        &fest_pro,
+        None,
+        fest_pro_area,
+        fest_pro_block,
+        &app.preferences,
+        &app.theme,
+        frame,
+    );
+}
+
+fn render_fest_info_widget(fest: &CurrentFest, area: Rect, theme: &Theme, frame: &mut Frame) {
+    let block = Block::bordered()
+        .title(Line::from(fest.title.clone()).centered())
+        .border_style(Style::new().fg(theme.fest));
+
+    let mut teams: Vec<Span> = Vec::new();
+    for (index, team) in fest.teams.iter().enumerate() {
+        if index != 0 {
+            teams.push(" vs ".dim());
+        }
+        teams.push(team.name.clone().bold().fg(theme.color(Color::Rgb(
+            team.color.r,
+            team.color.g,
+            team.color.b,
+        ))));
+    }
+
+    let phase = match &fest.state {
+        FestState::Scheduled => format!("Starts in {}", format_countdown(fest.start_time)),
+        FestState::FirstHalf => format!(
+            "First half, midterm results in {}",
+            format_countdown(fest.midterm_time)
+        ),
+        FestState::SecondHalf => {
+            format!("Second half, ends in {}", format_countdown(fest.end_time))
+        }
+        FestState::Closed => "Closed, waiting for the results".to_string(),
+        FestState::Unknown(state) => format!("Unknown state {state}"),
+    };
+
+    let content = vec![
+        Line::from(teams).centered(),
+        Line::from(phase.italic()).centered(),
+        Line::from(format_stage_times(fest.start_time, fest.end_time).dim()).centered(),
+    ];
+
+    frame.render_widget(Paragraph::new(content).block(block), area);
+}
+
+fn render_stages(app: &App, frame: &mut Frame, area: Rect) {
+    if app.schedules.vs_stages.is_empty() {
+        render_error_widget(
+            &app.theme,
+            frame,
+            area,
+            "No Data.",
+            "The stage list is not available, the program may be loading.",
+        );
+        return;
+    }
+
+    let time_now = Utc::now();
+    let format_next = |mode: Mode, stage_id: &str| -> Text {
+        let next = app
+            .schedules
+            .upcoming(mode, time_now)
+            .into_iter()
+            .find(|schedule| schedule.has_stage(stage_id));
+        match next {
+            Some(schedule) => Text::from(vec![
+                Line::from(format_next_time(schedule.get_start_time())),
+                Line::from(
+                    schedule
+                        .rule()
+                        .map(|rule| rule.name.clone())
+                        .unwrap_or_default()
+                        .dim()
+                        .italic(),
+                ),
+            ]),
+            None => Text::from("-".dim()),
+        }
+    };
+
+    let rows = app
+        .schedules
+        .vs_stages
+        .iter()
+        .skip(app.app_ui.stages.scroll_offset)
+        .map(|stage| {
+            Row::new(vec![
+                Text::from(
+                    stage
+                        .name
+                        .clone()
+                        .bold()
+                        .patch_style(app.theme.preference(app.preferences.get(&stage.id))),
+                ),
+                format_next(Mode::Regular, &stage.id),
+                format_next(Mode::AnarchyOpen, &stage.id),
+                format_next(Mode::AnarchySeries, &stage.id),
+                format_next(Mode::XBattle, &stage.id),
+                format_next(Mode::Challenge, &stage.id),
+            ])
+            .height(2)
+        });
+
+    let header = Row::new(vec![
+        "Stage",
+        "Regular",
+        "Anarchy Open",
+        "Anarchy Series",
+        "X Battle",
+        "Challenge",
+    ])
+    .underlined();
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Fill(2),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(header)
+    .column_spacing(1)
+    // The first stage is the one marked by `Action::Favorite` and `Dislike`
+    .highlight_symbol("> ")
+    .row_highlight_style(Modifier::REVERSED)
+    .block(
+        Block::bordered()
+            .border_style(Style::new().fg(app.theme.stages))
+            .title("Stages"),
+    );
+
+    frame.render_stateful_widget(table, area, &mut TableState::new().with_selected(0));
+}
+
+/// Format the start time of the next occurrence, or "Now" if it has already started.
+fn format_next_time(start_time: DateTime<Utc>) -> String {
+    if start_time <= Utc::now() {
+        return "Now".to_string();
+    }
+    let start_time: DateTime<Local> = DateTime::from(start_time);
+    if start_time.date_naive() == Local::now().date_naive() {
+        start_time.format("%H:%M").to_string()
+    } else {
+        start_time.format("%H:%M <%a>").to_string()
+    }
+}
+
+fn render_shop(app: &App, frame: &mut Frame, area: Rect) {
+    let pickup_brand = match &app.gear_shop.pickup_brand {
+        Some(pickup_brand) => pickup_brand,
+        None => {
+            let reason = match &app.gear_shop_error {
+                Some(err) => format!("The SplatNet 3 shop couldn't be loaded: {err}"),
+                None => {
+                    "The SplatNet 3 shop is not available, the program may be loading.".to_string()
+                }
+            };
+            render_error_widget(&app.theme, frame, area, "No Data.", &reason);
+            return;
+        }
+    };
+
+    let divided_areas: [_; 2] = if area.width > area.height * 2 {
+        Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)])
+            .flex(layout::Flex::SpaceAround)
+            .spacing(1)
+            .areas(area)
+    } else {
+        Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(area)
+    };
+
+    let mut daily_drop_title = vec![Span::from(format!(
+        "Daily Drop: {}",
+        pickup_brand.brand.name
+    ))];
+    if let Some(usual_power) = &pickup_brand.usual_power {
+        daily_drop_title.push(format!(" ({})", usual_power.name).italic());
+    }
+    let daily_drop_block = Block::bordered()
+        .border_style(Style::new().fg(app.theme.shop))
+        .title(Line::from(daily_drop_title))
+        .title_bottom(
+            Line::from(format_stage_times(Utc::now(), pickup_brand.sale_end_time).italic())
+                .right_aligned(),
+        );
+    render_gear_widget(
+        &pickup_brand.gears,
+        divided_areas[0],
+        daily_drop_block,
+        frame,
+    );
+
+    let limited_block = Block::bordered()
+        .border_style(Style::new().fg(app.theme.shop))
+        .title("Limited-Time Gear");
+    render_gear_widget(
+        &app.gear_shop.limited_gears,
+        divided_areas[1],
+        limited_block,
+        frame,
+    );
+}
+
+fn render_gear_widget(gears: &[GearSale], area: Rect, block: Block, frame: &mut Frame) {
+    let sub_area = block.inner(area);
+    let mut text: Vec<Line> = Vec::new();
+
+    for gear in gears {
+        text.push(format_schedule_title(
+            sub_area,
+            gear.name.name.clone(),
+            Style::new(),
+            Utc::now(),
+            gear.sale_end_time,
+        ));
+        let details = format!(
+            "{} / {} +{}",
+            gear.brand.name, gear.primary_power.name, gear.additional_slots
+        );
+        let price = format!("{} coins", gear.price);
+        let mid_space = fill_mid_spaces(&details, &price, sub_area);
+        text.push(Line::from(vec![
+            details.italic(),
+            mid_space.into(),
+            price.bold(),
+        ]));
+        text.push(Line::from(""));
+    }
+
+    frame.render_widget(Paragraph::new(text).block(block), area);
+}
+
+fn render_festivals(app: &App, frame: &mut Frame, area: Rect) {
+    let festivals = app.get_region_festivals();
+    if festivals.is_empty() {
+        let reason = match &app.festivals_error {
+            Some(err) => format!("The Splatfest records couldn't be loaded: {err}"),
+            None => {
+                "The Splatfest records are not available, the program may be loading.".to_string()
+            }
+        };
+        render_error_widget(&app.theme, frame, area, "No Data.", &reason);
+        return;
+    }
+
+    let [list_area, details_area] = Layout::horizontal([Constraint::Fill(1), Constraint::Fill(2)])
+        .flex(layout::Flex::SpaceAround)
+        .spacing(1)
+        .areas(area);
+
+    let items = festivals.iter().map(|festival| {
+        let start_time: DateTime<Local> = DateTime::from(festival.start_time);
+        Line::from(vec![
+            start_time.format("%Y-%m-%d ").to_string().dim(),
+            festival.title.clone().into(),
+        ])
+    });
+    let list = List::new(items).highlight_style(Modifier::REVERSED).block(
+        Block::bordered()
+            .border_style(Style::new().fg(app.theme.festivals))
+            .title(format!("Splatfests ({})", app.region)),
+    );
+    let mut list_state = ListState::default().with_selected(Some(app.app_ui.festivals.selected));
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+
+    if let Some(festival) = festivals.get(app.app_ui.festivals.selected) {
+        render_festival_details(festival, details_area, &app.theme, frame);
+    }
+}
+
+fn render_festival_details(festival: &Festival, area: Rect, theme: &Theme, frame: &mut Frame) {
+    let block = Block::bordered()
+        .title(Line::from(festival.title.clone()).centered())
+        .title_bottom(
+            Line::from(format_stage_times(festival.start_time, festival.end_time).italic())
+                .right_aligned(),
+        )
+        .border_style(Style::new().fg(theme.festivals));
+
+    let format_ratio = |ratio: Option<f64>| match ratio {
+        Some(ratio) => format!("{:.2}%", ratio * 100.0),
+        None => "-".to_string(),
+    };
+
+    let mut content: Vec<Line> = Vec::new();
+    if let Some(winner) = festival.winner() {
+        content.push(
+            Line::from(vec![
+                "Winner: ".into(),
+                winner.name.clone().bold().fg(theme.color(Color::Rgb(
+                    winner.color.r,
+                    winner.color.g,
+                    winner.color.b,
+                ))),
+            ])
+            .centered(),
+        );
+        content.push("".into());
+    }
+    for team in &festival.teams {
+        let team_name = team.name.clone().bold().fg(theme.color(Color::Rgb(
+            team.color.r,
+            team.color.g,
+            team.color.b,
+        )));
+        content.push(Line::from(team_name));
+        match &team.result {
+            Some(result) => {
+                for (label, ratio) in [
+                    ("Votes", result.vote_ratio),
+                    ("Conch Shells", result.horagai_ratio),
+                    ("Open", result.regular_contribution_ratio),
+                    ("Pro", result.challenge_contribution_ratio),
+                    ("Tricolor", result.tricolor_contribution_ratio),
+                ] {
+                    let ratio = format_ratio(ratio);
+                    content.push(Line::from(vec![
+                        format!("  {label}").italic(),
+                        fill_mid_spaces(&format!("  {label}"), &ratio, block.inner(area)).into(),
+                        ratio.into(),
+                    ]));
+                }
+            }
+            None => content.push(Line::from("  Results not announced yet.".dim().italic())),
+        }
+        content.push("".into());
+    }
+
+    frame.render_widget(Paragraph::new(content).block(block), area);
 }
 
 const ERR_WIDGET_WIDTH: u16 = 48;
 
-fn render_error_widget(frame: &mut Frame<'_>, area: Rect, title: &str, reason: &str) {
+fn render_error_widget(
+    theme: &Theme,
+    frame: &mut Frame<'_>,
+    area: Rect,
+    title: &str,
+    reason: &str,
+) {
     let error_msg = Paragraph::new(vec![
         Line::from(title).bold().centered(),
         Line::from(""),
@@ -415,7 +1039,7 @@ fn render_error_widget(frame: &mut Frame<'_>, area: Rect, title: &str, reason: &
         error_msg.wrap(ratatui::widgets::Wrap { trim: true }).block(
             Block::bordered()
                 .title("Error")
-                .border_style(Style::new().red()),
+                .border_style(Style::new().fg(theme.error)),
         ),
         center_single_block(
             area,
@@ -433,27 +1057,40 @@ fn render_error_widget(frame: &mut Frame<'_>, area: Rect, title: &str, reason: &
 fn format_schedule_title<'a>(
     sub_area: Rect,
     name: String,
+    style: Style,
     start_time: DateTime<Utc>,
     end_time: DateTime<Utc>,
 ) -> Line<'a> {
-    let rule = name.clone().bold().underlined();
+    let rule = name.clone().bold().underlined().patch_style(style);
     let time = format_stage_times(start_time, end_time).italic();
     let space = fill_mid_spaces(&rule.content, &time.content, sub_area).into();
     Line::from(vec![rule, space, time])
 }
 
 fn format_work_schedule_title<'a>(
+    theme: &Theme,
     sub_area: Rect,
     name: String,
+    style: Style,
     rule: &CoopRule,
     start_time: DateTime<Utc>,
     end_time: DateTime<Utc>,
 ) -> Line<'a> {
-    let stage_name = name.clone().bold();
+    let stage_name = name.clone().bold().patch_style(style);
     let rule = match rule {
         CoopRule::Regular => "".into(),
-        CoopRule::BigRun => " Big Run ".italic().bold().black().on_magenta(),
-        CoopRule::TeamContest => " Team Contest ".italic().bold().black().on_yellow(),
+        CoopRule::BigRun => " Big Run "
+            .italic()
+            .bold()
+            .patch_style(theme.badge(theme.big_run)),
+        CoopRule::TeamContest => " Team Contest "
+            .italic()
+            .bold()
+            .patch_style(theme.badge(theme.team_contest)),
+        CoopRule::Unknown(rule) => format!(" {rule} ")
+            .italic()
+            .bold()
+            .patch_style(theme.badge(theme.unknown_rule)),
     };
     let time = format_stage_times(start_time, end_time).italic();
     let spacer: Span = " ".into();
@@ -524,6 +1161,70 @@ fn format_stage_times(start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> Str
     }
 }
 
+fn render_tricolor_widget(
+    fest: &CurrentFest,
+    stage_name: &str,
+    area: Rect,
+    theme: &Theme,
+    frame: &mut Frame,
+) {
+    let block = Block::bordered()
+        .title(Line::from("Tricolor Turf War").centered())
+        .border_style(Style::new().fg(theme.fest));
+
+    let status = if fest.is_tricolor_active {
+        "Tricolor battles are live!".bold().fg(theme.live)
+    } else {
+        "Tricolor battles are not live".dim()
+    };
+    let midterm = if fest.midterm_time > Utc::now() {
+        format!("Midterm results in {}", format_countdown(fest.midterm_time))
+    } else {
+        "Midterm results announced".to_string()
+    };
+
+    let content = vec![
+        Line::from(stage_name.to_string().bold().underlined()).centered(),
+        Line::from(midterm.italic()).centered(),
+        Line::from(status).centered(),
+    ];
+
+    frame.render_widget(Paragraph::new(content).block(block), area);
+}
+
+fn format_countdown(target: DateTime<Utc>) -> String {
+    let time_now = Local::now().round_subsecs(0);
+    let remaining_time = max(
+        DateTime::<Local>::from(target) - time_now,
+        TimeDelta::zero(),
+    );
+    let mut res = String::new();
+    if remaining_time.num_days() != 0 {
+        res.push_str(&format!("{}d ", remaining_time.num_days()));
+    }
+    if remaining_time.num_hours() != 0 {
+        res.push_str(&format!("{}h ", remaining_time.num_hours() % 24));
+    }
+    res.push_str(&format!(
+        "{}m {:>2}s",
+        remaining_time.num_minutes() % 60,
+        remaining_time.num_seconds() % 60
+    ));
+    res
+}
+
+/// Format the time elapsed since `time`, with a precision of minutes.
+fn format_elapsed(time: DateTime<Utc>) -> String {
+    let elapsed = max(Utc::now() - time, TimeDelta::zero());
+    if elapsed.num_days() != 0 {
+        format!("{}d {}h", elapsed.num_days(), elapsed.num_hours() % 24)
+    } else if elapsed.num_hours() != 0 {
+        format!("{}h {}m", elapsed.num_hours(), elapsed.num_minutes() % 60)
+    } else {
+        format!("{}m", elapsed.num_minutes())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use ratatui::{
@@ -532,7 +1233,18 @@ mod test {
         widgets::Block,
     };
 
-    use super::fill_mid_spaces;
+    use chrono::{Duration, Utc};
+
+    use color_eyre::{Report, eyre::eyre};
+
+    use idacast::data::{DataError, schedules::NameID};
+
+    use crate::{
+        app::theme::Theme,
+        config::{Preference, Preferences},
+    };
+
+    use super::{entry_style, fill_mid_spaces, format_elapsed, suggest_action};
 
     #[test]
     fn test_fill_mid_spaces() {
@@ -554,5 +1266,67 @@ mod test {
             "                                      ".to_string()
         );
     }
+
+    #[test]
+    fn test_entry_style() {
+        let theme = Theme::default();
+        let mut preferences = Preferences::default();
+        preferences.toggle("stage", Preference::Disliked);
+        let stage = NameID {
+            name: "Scorch Gorge".to_string(),
+            id: "stage".to_string(),
+        };
+        assert_eq!(
+            entry_style(&theme, &preferences, &stage, false),
+            theme.preference(Some(Preference::Disliked))
+        );
+        assert_eq!(
+            entry_style(&theme, &preferences, &stage, true),
+            theme.preference(Some(Preference::Disliked)).reversed()
+        );
+    }
+
+    #[test]
+    fn test_format_elapsed() {
+        let now = Utc::now();
+        assert_eq!(format_elapsed(now - Duration::minutes(7)), "7m");
+        assert_eq!(
+            format_elapsed(now - Duration::hours(5) - Duration::minutes(12)),
+            "5h 12m"
+        );
+        assert_eq!(
+            format_elapsed(now - Duration::days(2) - Duration::hours(3)),
+            "2d 3h"
+        );
+        // Clock skew shouldn't produce negative durations
+        assert_eq!(format_elapsed(now + Duration::minutes(3)), "0m");
+    }
+
+    #[test]
+    fn test_suggest_action() {
+        let unreachable = Report::new(DataError::NetworkUnreachable {
+            url: "https://splatoon3.ink/data/schedules.json".to_string(),
+        });
+        assert!(suggest_action(&unreachable).contains("--offline"));
+
+        let server_error = Report::new(DataError::HttpStatus {
+            url: "https://splatoon3.ink/data/schedules.json".to_string(),
+            status: 503,
+        });
+        let not_found = Report::new(DataError::HttpStatus {
+            url: "https://example.com/schedules.json".to_string(),
+            status: 404,
+        });
+        assert_ne!(suggest_action(&server_error), suggest_action(&not_found));
+        assert!(suggest_action(&not_found).contains("--source"));
+
+        let corrupted = Report::new(DataError::CacheCorruption {
+            cache: "IDACAST_CACHE".to_string(),
+            message: "Storage error".to_string(),
+        });
+        assert!(suggest_action(&corrupted).contains("--clear-cache"));
+
+        assert_eq!(suggest_action(&eyre!("unknown")), "Try again later.");
+    }
     // TODO: I may need som tests for UI
 }
@@ -0,0 +1,281 @@
+//! Disk cache for the data fetched by `idacast::data`, which keeps track of when each entry was
+//! fetched and when it should be revalidated.
+use cached::{DiskCache, DiskCacheError, IOCached};
+use std::cmp::max;
+
+use chrono::{DateTime, Duration, Utc};
+use color_eyre::{Report, Result};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::data::{
+    DataError,
+    schedules::Schedules,
+    source::{Document, Validators},
+};
+
+/// Lifetime of the entries created with `CacheEntry::new`. Older entries can still be read, but
+/// should be revalidated.
+pub const CACHE_STORE_TTL: Duration = Duration::hours(3);
+pub const MIN_CACHE_LIFETIME: Duration = Duration::minutes(10);
+pub const MAX_CACHE_LIFETIME: Duration = Duration::hours(24);
+
+/// When a cached value was fetched from the data source, and when it should be refreshed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub fetched_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl CacheInfo {
+    /// Stale entries can still be displayed, but should be revalidated.
+    pub fn is_stale(&self, time: DateTime<Utc>) -> bool {
+        time >= self.expires_at
+    }
+}
+
+/// A cached value, along with its `CacheInfo` and the validators to revalidate it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry<V> {
+    pub info: CacheInfo,
+    pub validators: Validators,
+    pub value: V,
+}
+
+impl<V> CacheEntry<V> {
+    /// Cache a value that expires after `CACHE_STORE_TTL`.
+    pub fn new(value: V) -> Self {
+        let fetched_at = Utc::now();
+        CacheEntry {
+            info: CacheInfo {
+                fetched_at,
+                expires_at: fetched_at + CACHE_STORE_TTL,
+            },
+            validators: Validators::default(),
+            value,
+        }
+    }
+
+    pub fn is_stale(&self, time: DateTime<Utc>) -> bool {
+        self.info.is_stale(time)
+    }
+}
+
+impl CacheEntry<Schedules> {
+    /// Cache the schedules until new rotations are expected, see `Schedules::refresh_time`.
+    ///
+    /// The lifetime is kept between `MIN_CACHE_LIFETIME` and `MAX_CACHE_LIFETIME`, so that thin or
+    /// outdated data doesn't cause a flood of requests, and nothing is cached forever. The
+    /// `max-age` sent by the server is honored up to `MAX_CACHE_LIFETIME`.
+    pub fn with_schedules(document: Document<Schedules>) -> Self {
+        let fetched_at = Utc::now();
+        let expires_at = document
+            .value
+            .refresh_time()
+            .unwrap_or(fetched_at + CACHE_STORE_TTL)
+            .clamp(
+                fetched_at + MIN_CACHE_LIFETIME,
+                fetched_at + MAX_CACHE_LIFETIME,
+            );
+        let expires_at = match document
+            .max_age
+            .and_then(|max_age| fetched_at.checked_add_signed(max_age.min(MAX_CACHE_LIFETIME)))
+        {
+            Some(max_age_expiry) => max(expires_at, max_age_expiry),
+            None => expires_at,
+        };
+        CacheEntry {
+            info: CacheInfo {
+                fetched_at,
+                expires_at,
+            },
+            validators: document.validators,
+            value: document.value,
+        }
+    }
+
+    /// Renew an entry that the server reported as not modified.
+    pub fn renew(self, max_age: Option<Duration>) -> Self {
+        CacheEntry::with_schedules(Document {
+            value: self.value,
+            validators: self.validators,
+            max_age,
+        })
+    }
+}
+
+/// Disk cache that keeps the entries after they expire, so that they can be displayed when the
+/// data source can't be reached.
+pub struct CacheStore<V> {
+    name: String,
+    store: DiskCache<String, CacheEntry<V>>,
+}
+
+impl<V: Serialize + DeserializeOwned> CacheStore<V> {
+    /// Open the cache `name`, which fails with `DataError::CacheUnavailable` when its directory
+    /// can't be opened, e.g. when another process uses it.
+    pub fn new(name: &str) -> Result<Self, DataError> {
+        let store = DiskCache::new(name)
+            .set_refresh(false)
+            // The subcommands exit right after writing, before the periodic flush
+            .set_sync_to_disk_on_cache_change(true)
+            .build()
+            .map_err(|err| DataError::CacheUnavailable {
+                cache: name.to_string(),
+                message: err.to_string(),
+            })?;
+        Ok(CacheStore {
+            name: name.to_string(),
+            store,
+        })
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<CacheEntry<V>>> {
+        match self.store.cache_get(&key.to_string()) {
+            Ok(entry) => Ok(entry),
+            // Entries written by an older version can't be read, treat them as missing.
+            Err(DiskCacheError::CacheDeserializationError(_)) => Ok(None),
+            Err(err) => Err(self.corruption_error(err)),
+        }
+    }
+
+    pub fn set(&self, key: &str, entry: CacheEntry<V>) -> Result<()> {
+        self.store
+            .cache_set(key.to_string(), entry)
+            .map_err(|err| self.corruption_error(err))?;
+        Ok(())
+    }
+
+    fn corruption_error(&self, err: DiskCacheError) -> Report {
+        Report::new(DataError::CacheCorruption {
+            cache: self.name.clone(),
+            message: err.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use crate::data::{
+        schedules::{BattleSchedule, NameID, Schedules},
+        source::{Document, Validators},
+    };
+
+    use super::{CACHE_STORE_TTL, CacheEntry, CacheInfo, MAX_CACHE_LIFETIME, MIN_CACHE_LIFETIME};
+
+    fn get_test_schedules(start_time: chrono::DateTime<Utc>, count: i32) -> Schedules {
+        let rotations = (0..count)
+            .map(|index| BattleSchedule {
+                start_time: start_time + Duration::hours(2 * index as i64),
+                end_time: start_time + Duration::hours(2 * index as i64 + 2),
+                stages: vec![],
+                rule: NameID {
+                    name: "Splat Zones".to_string(),
+                    id: "VnNSdWxlLTE=".to_string(),
+                },
+            })
+            .collect();
+        Schedules {
+            regular: rotations,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cache_info_staleness() {
+        let info = CacheInfo {
+            fetched_at: Utc.with_ymd_and_hms(2025, 11, 8, 0, 0, 0).unwrap(),
+            expires_at: Utc.with_ymd_and_hms(2025, 11, 8, 3, 0, 0).unwrap(),
+        };
+        assert!(!info.is_stale(info.fetched_at + Duration::minutes(30)));
+        assert!(info.is_stale(info.expires_at));
+        assert!(info.is_stale(info.fetched_at + Duration::days(2)));
+    }
+
+    #[test]
+    fn test_cache_entry_expiry() {
+        let entry = CacheEntry::new(());
+        assert_eq!(
+            entry.info.expires_at - entry.info.fetched_at,
+            CACHE_STORE_TTL
+        );
+
+        // 12 rotations starting from the current one: refresh when 3 upcoming ones are left
+        let now = Utc::now();
+        let entry = CacheEntry::with_schedules(Document::new(get_test_schedules(
+            now - Duration::hours(1),
+            12,
+        )));
+        assert_eq!(
+            entry.info.expires_at,
+            now - Duration::hours(1) + Duration::hours(18)
+        );
+
+        // Data that is already outdated shouldn't be refreshed right away
+        let entry = CacheEntry::with_schedules(Document::new(get_test_schedules(
+            now - Duration::days(7),
+            2,
+        )));
+        assert_eq!(
+            entry.info.expires_at - entry.info.fetched_at,
+            MIN_CACHE_LIFETIME
+        );
+
+        // Far future data is still refreshed eventually
+        let entry = CacheEntry::with_schedules(Document::new(get_test_schedules(now, 120)));
+        assert_eq!(
+            entry.info.expires_at - entry.info.fetched_at,
+            MAX_CACHE_LIFETIME
+        );
+
+        let entry = CacheEntry::with_schedules(Document::new(Schedules::default()));
+        assert_eq!(
+            entry.info.expires_at - entry.info.fetched_at,
+            CACHE_STORE_TTL
+        );
+    }
+
+    #[test]
+    fn test_cache_entry_max_age() {
+        let now = Utc::now();
+        let validators = Validators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        let entry = CacheEntry::with_schedules(Document {
+            value: get_test_schedules(now - Duration::days(7), 2),
+            validators: validators.clone(),
+            max_age: Some(Duration::hours(1)),
+        });
+        assert_eq!(
+            entry.info.expires_at - entry.info.fetched_at,
+            Duration::hours(1)
+        );
+        assert_eq!(entry.validators, validators);
+
+        // The validators are kept when renewing a not modified entry
+        let renewed = entry.renew(None);
+        assert_eq!(
+            renewed.info.expires_at - renewed.info.fetched_at,
+            MIN_CACHE_LIFETIME
+        );
+        assert_eq!(renewed.validators, validators);
+    }
+
+    #[test]
+    fn test_cache_entry_huge_max_age() {
+        let now = Utc::now();
+        for max_age in [Duration::days(365), Duration::MAX] {
+            let entry = CacheEntry::with_schedules(Document {
+                value: get_test_schedules(now, 2),
+                validators: Validators::default(),
+                max_age: Some(max_age),
+            });
+            assert_eq!(
+                entry.info.expires_at - entry.info.fetched_at,
+                MAX_CACHE_LIFETIME
+            );
+        }
+    }
+}
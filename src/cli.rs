@@ -0,0 +1,327 @@
+//! Non-interactive subcommands, which print the schedules instead of starting the TUI.
+use chrono::{DateTime, Local, Utc};
+use clap::{Args, Subcommand};
+use color_eyre::Result;
+use idacast::{
+    cache::{CacheEntry, CacheStore},
+    data::{
+        export::{Export, ExportedRotation, VersionedRotation},
+        get_schedules,
+        ics::to_ics,
+        query::{Mode, RotationFilter, ScheduleMatch},
+        schedules::{Schedule, Schedules},
+        source::{DataSource, Document},
+    },
+};
+use strum_macros::{Display, EnumString};
+
+use crate::app::{load_schedules, schedules_cache_store};
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum Command {
+    /// Print the current rotation of every mode.
+    Now,
+    /// Print the upcoming rotations of a mode, starting with the current one.
+    Next {
+        /// One of regular, anarchy-series (series), anarchy-open (open), x-battle (x), challenge,
+        /// fest-open, fest-pro, work (salmon-run), big-run or team-contest.
+        #[arg(short, long, default_value_t = Mode::Regular)]
+        mode: Mode,
+        #[arg(short, long, default_value_t = 3)]
+        count: usize,
+    },
+    /// Print the upcoming Salmon Run shifts, including Big Runs and Team Contests.
+    Work,
+    /// Export the upcoming rotations of every mode to other programs.
+    Export {
+        #[command(subcommand)]
+        target: ExportTarget,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum ExportTarget {
+    /// iCalendar, one event per rotation, Salmon Run shift, and Challenge time period.
+    Ics(ExportFilter),
+}
+
+/// Keep the rotations matching every given filter.
+#[derive(Args, Debug, Clone, Default)]
+pub(crate) struct ExportFilter {
+    /// Mode to export, can be repeated. Same values as `next --mode`.
+    #[arg(short, long)]
+    mode: Vec<Mode>,
+    /// Name (or part of it) or id of the rule, e.g. "Splat Zones".
+    #[arg(long)]
+    rule: Option<String>,
+    /// Name (or part of it) or id of a stage, e.g. "Museum".
+    #[arg(long)]
+    stage: Option<String>,
+}
+
+impl ExportFilter {
+    fn matches(&self, schedule: &ScheduleMatch) -> bool {
+        RotationFilter {
+            mode: &self.mode,
+            rule: self.rule.as_deref(),
+            stage: self.stage.as_deref(),
+            weapon: None,
+        }
+        .matches(schedule)
+    }
+}
+
+/// How the subcommands print the rotations. The machine readable formats follow the schema of
+/// `idacast::data::export`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub(crate) enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    /// One JSON object per line and per rotation.
+    Ndjson,
+    Yaml,
+}
+
+/// Print the schedules for `command`, from the cache if it's fresh, like the TUI does. Stale
+/// schedules are printed when they can't be refreshed.
+pub(crate) async fn run(
+    command: Command,
+    format: OutputFormat,
+    source: Option<&dyn DataSource>,
+    lang: Option<String>,
+    cache_key: &str,
+) -> Result<()> {
+    let entry = load_entry(schedules_cache_store(), source, lang, cache_key).await?;
+    let time_now = Utc::now();
+    if entry.is_stale(time_now) {
+        let fetched_at: DateTime<Local> = DateTime::from(entry.info.fetched_at);
+        eprintln!(
+            "Showing cached schedules fetched at {}",
+            fetched_at.format("%Y-%m-%d %H:%M")
+        );
+    }
+    let schedules = entry.value.without_ended(time_now);
+    for warning in &schedules.warnings {
+        eprintln!("Partial data: {warning}");
+    }
+
+    let rotations = match command {
+        Command::Export {
+            target: ExportTarget::Ics(filter),
+        } => {
+            let rotations = schedules
+                .find(|schedule| schedule.get_end_time() > time_now && filter.matches(schedule));
+            print!("{}", to_ics(&rotations, time_now));
+            return Ok(());
+        }
+        Command::Now => schedules.at(time_now),
+        Command::Next { mode, count } => schedules.next_n(mode, count),
+        Command::Work => schedules.find(|schedule| {
+            matches!(schedule.mode, Mode::Work | Mode::BigRun | Mode::TeamContest)
+                && schedule.get_end_time() > time_now
+        }),
+    };
+    if rotations.is_empty() && format == OutputFormat::Text {
+        eprintln!("Nothing is scheduled.");
+    }
+    print!("{}", format_output(&rotations, format)?);
+    Ok(())
+}
+
+/// Load the schedules from `cache_store`, or fetch them without it when it can't be opened, e.g.
+/// while the TUI or another subcommand is using it.
+async fn load_entry(
+    cache_store: Result<&CacheStore<Schedules>>,
+    source: Option<&dyn DataSource>,
+    lang: Option<String>,
+    cache_key: &str,
+) -> Result<CacheEntry<Schedules>> {
+    let cache_store = match (cache_store, source) {
+        (Ok(cache_store), _) => cache_store,
+        (Err(err), Some(source)) => {
+            eprintln!("Fetching the schedules without the cache: {err}");
+            let schedules = get_schedules(source, lang).await?;
+            return Ok(CacheEntry::with_schedules(Document::new(schedules)));
+        }
+        (Err(err), None) => return Err(err),
+    };
+    let mut stale = None;
+    let loaded = load_schedules(cache_store, source, lang, cache_key, true, |entry| {
+        stale = Some(entry.clone())
+    })
+    .await;
+    match (loaded, stale) {
+        (Ok((entry, _)), _) => Ok(entry),
+        // e.g. the network is down, the stale schedules are better than nothing
+        (Err(err), Some(entry)) => {
+            eprintln!("Failed to refresh the schedules: {err}");
+            Ok(entry)
+        }
+        (Err(err), None) => Err(err),
+    }
+}
+
+fn format_output(rotations: &[ScheduleMatch], format: OutputFormat) -> Result<String> {
+    let mut res = match format {
+        OutputFormat::Text => rotations
+            .iter()
+            .map(format_rotation)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        OutputFormat::Json => serde_json::to_string_pretty(&Export::new(rotations))?,
+        OutputFormat::Ndjson => rotations
+            .iter()
+            .map(|rotation| {
+                serde_json::to_string(&VersionedRotation::from(ExportedRotation::from(rotation)))
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .join("\n"),
+        // Already ends with a newline
+        OutputFormat::Yaml => return Ok(serde_norway::to_string(&Export::new(rotations))?),
+    };
+    if !res.is_empty() {
+        res.push('\n');
+    }
+    Ok(res)
+}
+
+fn format_times(start_time: DateTime<Utc>, end_time: DateTime<Utc>) -> String {
+    let start_time: DateTime<Local> = DateTime::from(start_time);
+    let end_time: DateTime<Local> = DateTime::from(end_time);
+    if start_time.date_naive() == end_time.date_naive() {
+        format!(
+            "{} - {}",
+            start_time.format("%a %H:%M"),
+            end_time.format("%H:%M")
+        )
+    } else {
+        format!(
+            "{} - {}",
+            start_time.format("%a %H:%M"),
+            end_time.format("%a %H:%M")
+        )
+    }
+}
+
+/// One line per rotation: mode, times, then what's played and where.
+fn format_rotation(schedule: &ScheduleMatch) -> String {
+    let times = format_times(schedule.get_start_time(), schedule.get_end_time());
+    format!(
+        "{:<15} {times:<25} {}",
+        schedule.mode.name(),
+        schedule.details()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use color_eyre::eyre::eyre;
+    use idacast::data::{get_schedules, query::Mode, schedules::Schedules, source::MemorySource};
+
+    use super::{ExportFilter, OutputFormat, format_output, format_rotation, load_entry};
+
+    fn get_sample_source() -> MemorySource {
+        MemorySource::from([(
+            "schedules.json",
+            include_str!("../resources/samples/teamcontest/schedules.json"),
+        )])
+    }
+
+    async fn get_sample() -> Schedules {
+        get_schedules(&get_sample_source(), None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_load_entry_without_cache() {
+        // e.g. the cache is locked by the TUI
+        let entry = load_entry(
+            Err(eyre!("cache locked")),
+            Some(&get_sample_source()),
+            None,
+            "default",
+        )
+        .await
+        .unwrap();
+        assert_eq!(entry.value, get_sample().await);
+
+        // There's nothing to display in offline mode
+        let res = load_entry(Err(eyre!("cache locked")), None, None, "default").await;
+        assert_eq!(res.unwrap_err().to_string(), "cache locked");
+    }
+
+    #[tokio::test]
+    async fn test_format_output() {
+        let schedules = get_sample().await;
+        let rotations = [
+            schedules.rotations(Mode::Regular)[0],
+            schedules.rotations(Mode::XBattle)[0],
+        ];
+
+        let ndjson = format_output(&rotations, OutputFormat::Ndjson).unwrap();
+        let lines: Vec<serde_json::Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["version"], 1);
+        assert_eq!(lines[1]["mode"], "x-battle");
+        assert_eq!(lines[1]["end_time"], "2025-11-08T14:00:00Z");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&format_output(&rotations, OutputFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["rotations"][0], {
+            let mut line = lines[0].clone();
+            line.as_object_mut().unwrap().remove("version");
+            line
+        });
+
+        let yaml: serde_json::Value =
+            serde_norway::from_str(&format_output(&rotations, OutputFormat::Yaml).unwrap())
+                .unwrap();
+        assert_eq!(yaml, json);
+
+        assert_eq!(format_output(&[], OutputFormat::Text).unwrap(), "");
+        assert_eq!(format_output(&[], OutputFormat::Ndjson).unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_format_rotation() {
+        let schedules = get_sample().await;
+        let line = format_rotation(&schedules.rotations(Mode::XBattle)[0]);
+        assert!(line.starts_with("X Battle "));
+        assert!(line.ends_with(" Tower Control: Eeltail Alley, Wahoo World"));
+
+        let line = format_rotation(&schedules.rotations(Mode::Work)[0]);
+        assert!(line.starts_with("Salmon Run "));
+        assert!(line.ends_with(
+            " Bonerattle Arena vs Horrorboros: \
+             Dread Wringer, Splattershot, Recycled Brella 24 Mk I, Jet Squelcher"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_export_filter() {
+        let schedules = get_sample().await;
+        let rotation = schedules.rotations(Mode::XBattle)[0];
+        assert!(ExportFilter::default().matches(&rotation));
+
+        let filter = ExportFilter {
+            mode: vec![Mode::Regular, Mode::XBattle],
+            rule: Some("tower".to_string()),
+            stage: Some("Eeltail Alley".to_string()),
+        };
+        assert!(filter.matches(&rotation));
+        let filter = ExportFilter {
+            mode: vec![Mode::Challenge],
+            ..Default::default()
+        };
+        assert!(!filter.matches(&rotation));
+        let filter = ExportFilter {
+            stage: Some("Museum".to_string()),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&rotation));
+    }
+}
@@ -0,0 +1,610 @@
+//! User configuration, read from `$XDG_CONFIG_HOME/idacast/config.toml`.
+//!
+//! Every setting is optional. The defaults are overridden by the config file, which is overridden
+//! by the environment (`IDACAST_*`) and then by the command line, see `Args::apply` in `main.rs`.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Duration;
+use clap::Subcommand;
+use color_eyre::{Result, eyre::eyre};
+use directories::ProjectDirs;
+use idacast::{
+    cache::MIN_CACHE_LIFETIME,
+    data::{alerts::AlertRule, festivals::Region},
+};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::app::{
+    AppScreen, RETRY_BASE_DELAY, RETRY_MAX_DELAY,
+    keymap::{Action, Keymap},
+    theme::{ColorDepth, Theme, ThemeName},
+};
+
+const CONFIG_FILE_NAME: &str = "config.toml";
+const PREFERENCES_FILE_NAME: &str = "preferences.toml";
+
+#[derive(Subcommand, Debug, Clone)]
+pub(crate) enum ConfigCommand {
+    /// Write the default configuration to the config file.
+    Init {
+        /// Overwrite the config file if it already exists.
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the configuration in effect, after applying the environment and the flags.
+    Show,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Config {
+    /// Language of the translations, e.g. "ja-JP". The OS's language setting if unset.
+    pub language: Option<String>,
+    /// Region of the Splatfests in the festivals tab.
+    pub region: Region,
+    /// Base URL of a splatoon3.ink mirror, or a local directory with the same layout.
+    pub source: Option<String>,
+    /// Only display what's in the cache.
+    pub offline: bool,
+    /// Capture the mouse to scroll with the wheel.
+    pub mouse: bool,
+    pub refresh: RefreshPolicy,
+    pub tabs: TabsConfig,
+    pub theme: ThemeConfig,
+    /// Keys to bind in addition to the default ones, e.g. `x = "quit"` or `q = "noop"` to unbind
+    /// a default key. See `Keymap` for the names of the keys and the actions.
+    pub keybindings: BTreeMap<String, Action>,
+    pub preferences: Preferences,
+    pub alerts: AlertsConfig,
+}
+
+/// When the schedules are updated, see `App::handle_auto_update`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct RefreshPolicy {
+    /// Refresh when the schedules expire, and retry failed updates. Otherwise, only refresh when
+    /// requested.
+    pub auto_update: bool,
+    /// Delay before retrying a failed update, doubled after every failure.
+    pub retry_delay_secs: u32,
+    pub max_retry_delay_secs: u32,
+    /// Minimum delay between two auto updates.
+    pub min_interval_secs: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct TabsConfig {
+    /// The tab displayed on startup, the first visible tab if unset.
+    pub default: Option<AppScreen>,
+    /// The tabs in the header, in this order. The other tabs can't be opened.
+    pub visible: Vec<AppScreen>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct ThemeConfig {
+    pub name: ThemeName,
+    /// Detected from the environment if unset, see `ColorDepth::detect`.
+    pub color_depth: Option<ColorDepth>,
+    /// Colors replacing the ones of the theme, with the same names as the fields of `Theme`.
+    pub colors: toml::Table,
+}
+
+/// Stages and rules to highlight or dim, by id so that they're the same in every language. The ids
+/// are in the JSON output, e.g. `idacast now --format json`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct Preferences {
+    pub favorite: BTreeSet<String>,
+    pub disliked: BTreeSet<String>,
+}
+
+/// How the preferences marked in the TUI differ from the `[preferences]` of the config file. They
+/// are saved to their own file, so that the config file is never rewritten.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct PreferenceMarks {
+    pub favorite: BTreeSet<String>,
+    pub disliked: BTreeSet<String>,
+    /// Marked in the config file, but unmarked in the TUI.
+    pub unmarked: BTreeSet<String>,
+}
+
+/// Reminders of upcoming rotations, delivered while the TUI is running, whatever the tab.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub(crate) struct AlertsConfig {
+    /// Ring the terminal bell.
+    pub bell: bool,
+    /// Ask the terminal to show a desktop notification.
+    pub notification: Option<Notification>,
+    /// Shell command run for each alert, see `app::alerts::deliver` for its environment.
+    pub command: Option<String>,
+    pub rules: Vec<AlertRule>,
+}
+
+/// Escape sequences of desktop notifications, supported by different terminals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Notification {
+    /// OSC 9, e.g. iTerm2, Windows Terminal, WezTerm and kitty.
+    Osc9,
+    /// OSC 777, e.g. foot, Ghostty and the terminals based on VTE.
+    Osc777,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Preference {
+    Favorite,
+    Disliked,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            language: None,
+            region: Region::default(),
+            source: None,
+            offline: false,
+            mouse: true,
+            refresh: RefreshPolicy::default(),
+            tabs: TabsConfig::default(),
+            theme: ThemeConfig::default(),
+            keybindings: BTreeMap::new(),
+            preferences: Preferences::default(),
+            alerts: AlertsConfig::default(),
+        }
+    }
+}
+
+impl Default for RefreshPolicy {
+    fn default() -> Self {
+        RefreshPolicy {
+            auto_update: true,
+            retry_delay_secs: RETRY_BASE_DELAY.num_seconds() as u32,
+            max_retry_delay_secs: RETRY_MAX_DELAY.num_seconds() as u32,
+            min_interval_secs: MIN_CACHE_LIFETIME.num_seconds() as u32,
+        }
+    }
+}
+
+impl Default for AlertsConfig {
+    fn default() -> Self {
+        AlertsConfig {
+            bell: true,
+            notification: None,
+            command: None,
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl Default for TabsConfig {
+    fn default() -> Self {
+        TabsConfig {
+            default: None,
+            visible: AppScreen::iter().collect(),
+        }
+    }
+}
+
+impl TabsConfig {
+    pub fn startup_tab(&self) -> AppScreen {
+        self.default
+            .or_else(|| self.visible.first().copied())
+            .unwrap_or_default()
+    }
+}
+
+impl RefreshPolicy {
+    pub fn retry_delay(&self) -> Duration {
+        Duration::seconds(self.retry_delay_secs.into())
+    }
+
+    pub fn max_retry_delay(&self) -> Duration {
+        Duration::seconds(self.max_retry_delay_secs.into())
+    }
+
+    pub fn min_interval(&self) -> Duration {
+        Duration::seconds(self.min_interval_secs.into())
+    }
+}
+
+impl Preferences {
+    pub fn get(&self, id: &str) -> Option<Preference> {
+        if self.favorite.contains(id) {
+            Some(Preference::Favorite)
+        } else if self.disliked.contains(id) {
+            Some(Preference::Disliked)
+        } else {
+            None
+        }
+    }
+
+    /// Mark `id` with `preference`, or unmark it if it already is. Returns the new preference.
+    pub fn toggle(&mut self, id: &str, preference: Preference) -> Option<Preference> {
+        let marked = self.get(id) == Some(preference);
+        self.favorite.remove(id);
+        self.disliked.remove(id);
+        if marked {
+            return None;
+        }
+        match preference {
+            Preference::Favorite => self.favorite.insert(id.to_string()),
+            Preference::Disliked => self.disliked.insert(id.to_string()),
+        };
+        Some(preference)
+    }
+
+    /// Apply the marks saved by the TUI over these preferences.
+    pub fn apply(&mut self, marks: &PreferenceMarks) {
+        for id in &marks.unmarked {
+            self.favorite.remove(id);
+            self.disliked.remove(id);
+        }
+        for id in &marks.favorite {
+            self.disliked.remove(id);
+            self.favorite.insert(id.clone());
+        }
+        for id in &marks.disliked {
+            self.favorite.remove(id);
+            self.disliked.insert(id.clone());
+        }
+    }
+
+    /// The marks that turn `base` into these preferences.
+    pub fn marks_since(&self, base: &Preferences) -> PreferenceMarks {
+        PreferenceMarks {
+            favorite: self.favorite.difference(&base.favorite).cloned().collect(),
+            disliked: self.disliked.difference(&base.disliked).cloned().collect(),
+            unmarked: base
+                .favorite
+                .union(&base.disliked)
+                .filter(|id| self.get(id).is_none())
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl PreferenceMarks {
+    /// `$XDG_DATA_HOME/idacast/preferences.toml` on Linux, the usual data directory elsewhere.
+    pub fn default_path() -> Result<PathBuf> {
+        ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))
+            .map(|dirs| dirs.data_dir().join(PREFERENCES_FILE_NAME))
+            .ok_or_else(|| eyre!("Failed to find the data directory, no home directory is set"))
+    }
+
+    /// Read the marks saved at `path`, or none if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|err| eyre!("Invalid preferences file {}: {err}", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                Ok(PreferenceMarks::default())
+            }
+            Err(err) => Err(eyre!("Failed to read {}: {err}", path.display())),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, toml::to_string(self)?)
+            .map_err(|err| eyre!("Failed to write {}: {err}", path.display()))
+    }
+}
+
+impl ThemeConfig {
+    pub fn build(&self) -> Result<Theme> {
+        let depth = self.color_depth.unwrap_or_else(ColorDepth::detect);
+        Theme::new(self.name, &self.colors, depth)
+    }
+}
+
+impl Config {
+    /// `$XDG_CONFIG_HOME/idacast/config.toml` on Linux, the usual config directory elsewhere.
+    pub fn default_path() -> Result<PathBuf> {
+        ProjectDirs::from("", "", env!("CARGO_PKG_NAME"))
+            .map(|dirs| dirs.config_dir().join(CONFIG_FILE_NAME))
+            .ok_or_else(|| eyre!("Failed to find the config directory, no home directory is set"))
+    }
+
+    /// Read the config file at `path`, or the defaults if it doesn't exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(text) => Config::parse(&text)
+                .map_err(|err| eyre!("Invalid config file {}: {err}", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(eyre!("Failed to read {}: {err}", path.display())),
+        }
+    }
+
+    /// Only the syntax is checked, the settings are validated once the command line is applied.
+    fn parse(text: &str) -> Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// Reject the settings that the TUI can't work with, once every layer is applied.
+    pub fn validate(&self) -> Result<()> {
+        if self.tabs.visible.is_empty() {
+            return Err(eyre!("tabs.visible: at least one tab must be visible"));
+        }
+        if let Some(default) = self.tabs.default
+            && !self.tabs.visible.contains(&default)
+        {
+            return Err(eyre!(
+                "tabs.default: {default} is not one of the visible tabs"
+            ));
+        }
+        if self.refresh.retry_delay_secs == 0 || self.refresh.min_interval_secs == 0 {
+            return Err(eyre!(
+                "refresh: retry_delay_secs and min_interval_secs must be positive"
+            ));
+        }
+        if self.refresh.max_retry_delay_secs < self.refresh.retry_delay_secs {
+            return Err(eyre!(
+                "refresh: max_retry_delay_secs must not be less than retry_delay_secs"
+            ));
+        }
+        if let Some(id) = self
+            .preferences
+            .favorite
+            .intersection(&self.preferences.disliked)
+            .next()
+        {
+            return Err(eyre!(
+                "preferences: {id} can't be both a favorite and disliked"
+            ));
+        }
+        if self.alerts.rules.iter().any(AlertRule::is_empty) {
+            return Err(eyre!(
+                "alerts.rules: every rule needs a mode, rule, stage or weapon"
+            ));
+        }
+        Keymap::new(&self.keybindings)?;
+        self.theme.build()?;
+        Ok(())
+    }
+
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+/// Run `idacast config` on the config file at `path`. `load` reads the configuration in effect,
+/// which isn't needed to replace the file, so that an invalid file can be reset.
+pub(crate) fn run(
+    command: ConfigCommand,
+    path: &Path,
+    load: impl FnOnce() -> Result<Config>,
+) -> Result<()> {
+    match command {
+        ConfigCommand::Init { force } => {
+            if path.exists() && !force {
+                return Err(eyre!(
+                    "{} already exists, use --force to overwrite it",
+                    path.display()
+                ));
+            }
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            fs::write(path, Config::default().to_toml()?)?;
+            println!("Wrote the default configuration to {}", path.display());
+        }
+        ConfigCommand::Show => {
+            let mut config = load()?;
+            let validated = config.validate();
+            // Every color of the theme, so that they're easy to tweak
+            if let Ok(theme) = Theme::new(
+                config.theme.name,
+                &config.theme.colors,
+                ColorDepth::TrueColor,
+            ) {
+                config.theme.colors = toml::Table::try_from(theme)?;
+            }
+            println!("# {}", path.display());
+            print!("{}", config.to_toml()?);
+            // After the settings, which may come from the environment or the command line
+            validated.map_err(|err| err.wrap_err("The configuration in effect is invalid"))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use idacast::data::{festivals::Region, query::Mode};
+    use ratatui::style::Color;
+
+    use crate::app::{
+        AppScreen,
+        keymap::Action,
+        theme::{ColorDepth, ThemeName},
+    };
+
+    use super::{Config, ConfigCommand, Notification, Preference, PreferenceMarks, Preferences};
+
+    #[test]
+    fn test_default_config_roundtrip() {
+        let config = Config::default();
+        let text = config.to_toml().unwrap();
+        assert_eq!(Config::parse(&text).unwrap(), config);
+        assert_eq!(Config::parse("").unwrap(), config);
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r##"
+            language = "ja-JP"
+            region = "JP"
+
+            [refresh]
+            auto_update = false
+
+            [tabs]
+            default = "work"
+            visible = ["work", "battles"]
+
+            [theme]
+            name = "light"
+            color_depth = "256"
+            colors.tabs.work = "red"
+
+            [keybindings]
+            x = "quit"
+            ctrl-n = "next-tab"
+
+            [preferences]
+            favorite = ["VnNTdGFnZS0x"]
+            disliked = ["VnNSdWxlLTU="]
+
+            [alerts]
+            notification = "osc777"
+
+            [[alerts.rules]]
+            mode = ["x-battle"]
+            rule = "Clam Blitz"
+            minutes_before = 10
+
+            [[alerts.rules]]
+            mode = ["work", "big-run"]
+            weapon = "Grizzco"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.language.as_deref(), Some("ja-JP"));
+        assert_eq!(config.region, Region::JP);
+        assert!(!config.refresh.auto_update);
+        assert_eq!(config.refresh.retry_delay_secs, 15);
+        assert_eq!(config.tabs.default, Some(AppScreen::Work));
+        assert_eq!(
+            config.tabs.visible,
+            vec![AppScreen::Work, AppScreen::Battles]
+        );
+        assert_eq!(config.theme.name, ThemeName::Light);
+        assert_eq!(config.theme.color_depth, Some(ColorDepth::Ansi256));
+        assert_eq!(config.theme.build().unwrap().tabs.work, Color::Red);
+        assert_eq!(config.keybindings.get("x"), Some(&Action::Quit));
+        assert_eq!(config.keybindings.get("ctrl-n"), Some(&Action::NextTab));
+        assert_eq!(
+            config.preferences.get("VnNTdGFnZS0x"),
+            Some(Preference::Favorite)
+        );
+        assert_eq!(
+            config.preferences.get("VnNSdWxlLTU="),
+            Some(Preference::Disliked)
+        );
+        assert!(config.alerts.bell);
+        assert_eq!(config.alerts.notification, Some(Notification::Osc777));
+        assert_eq!(config.alerts.rules.len(), 2);
+        assert_eq!(config.alerts.rules[0].mode, vec![Mode::XBattle]);
+        assert_eq!(config.alerts.rules[0].minutes_before, 10);
+        assert_eq!(config.alerts.rules[1].weapon.as_deref(), Some("Grizzco"));
+    }
+
+    #[test]
+    fn test_parse_invalid_config() {
+        let validate = |text| Config::parse(text).and_then(|config| config.validate());
+        assert!(validate("langauge = \"ja-JP\"").is_err());
+        assert!(validate("[tabs]\nvisible = []").is_err());
+        assert!(validate("[tabs]\ndefault = \"shop\"\nvisible = [\"battles\"]").is_err());
+        assert!(validate("[refresh]\nretry_delay_secs = 0").is_err());
+        assert!(validate("[theme]\nname = \"solarized\"").is_err());
+        assert!(validate("[theme.colors]\nanarchy = \"not a color\"").is_err());
+        assert!(validate("[keybindings]\nx = \"jump\"").is_err());
+        assert!(validate("[[alerts.rules]]\nminutes_before = 5").is_err());
+        assert!(validate("[alerts]\nnotification = \"osc99\"").is_err());
+        assert!(validate("[keybindings]\nmeta-x = \"quit\"").is_err());
+        assert!(validate("[preferences]\nfavorite = [\"a\"]\ndisliked = [\"a\"]").is_err());
+    }
+
+    #[test]
+    fn test_tabs_override() {
+        // The tab given on the command line replaces the invalid one of the file
+        let mut config =
+            Config::parse("[tabs]\ndefault = \"shop\"\nvisible = [\"work\", \"battles\"]").unwrap();
+        config.tabs.default = Some(AppScreen::Battles);
+        assert!(config.validate().is_ok());
+        assert_eq!(config.tabs.startup_tab(), AppScreen::Battles);
+
+        config.tabs.default = None;
+        assert_eq!(config.tabs.startup_tab(), AppScreen::Work);
+    }
+
+    #[test]
+    fn test_toggle_preferences() {
+        let mut preferences = Preferences::default();
+        assert_eq!(
+            preferences.toggle("stage", Preference::Favorite),
+            Some(Preference::Favorite)
+        );
+        assert_eq!(preferences.get("stage"), Some(Preference::Favorite));
+        // Disliking a favorite replaces it
+        assert_eq!(
+            preferences.toggle("stage", Preference::Disliked),
+            Some(Preference::Disliked)
+        );
+        assert!(preferences.favorite.is_empty());
+        assert_eq!(preferences.toggle("stage", Preference::Disliked), None);
+        assert_eq!(preferences, Preferences::default());
+    }
+
+    #[test]
+    fn test_preference_marks() {
+        let mut config = Preferences::default();
+        config.toggle("liked", Preference::Favorite);
+        config.toggle("changed", Preference::Favorite);
+        config.toggle("unmarked", Preference::Disliked);
+        let mut preferences = config.clone();
+        preferences.toggle("changed", Preference::Disliked);
+        preferences.toggle("unmarked", Preference::Disliked);
+        preferences.toggle("new", Preference::Favorite);
+
+        let marks = preferences.marks_since(&config);
+        assert_eq!(marks.favorite, ["new".to_string()].into());
+        assert_eq!(marks.disliked, ["changed".to_string()].into());
+        assert_eq!(marks.unmarked, ["unmarked".to_string()].into());
+
+        let path = std::env::temp_dir().join(format!(
+            "idacast-test-preferences-{}.toml",
+            std::process::id()
+        ));
+        marks.save(&path).unwrap();
+        let saved = PreferenceMarks::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(saved, marks);
+        assert_eq!(
+            PreferenceMarks::load(&path).unwrap(),
+            PreferenceMarks::default()
+        );
+
+        config.apply(&saved);
+        assert_eq!(config, preferences);
+    }
+
+    #[test]
+    fn test_init_invalid_config() {
+        let path =
+            std::env::temp_dir().join(format!("idacast-test-config-{}.toml", std::process::id()));
+        std::fs::write(&path, "[tabs]\nvisible = []\n").unwrap();
+        let load = || Config::load(&path);
+        assert!(load().unwrap().validate().is_err());
+
+        let init = |force| super::run(ConfigCommand::Init { force }, &path, load);
+        assert!(init(false).is_err());
+        init(true).unwrap();
+        let config = load();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config.unwrap(), Config::default());
+    }
+}
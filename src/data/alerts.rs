@@ -0,0 +1,223 @@
+//! Reminders of upcoming rotations, e.g. "X Battle + Clam Blitz" or "Big Run", a few minutes
+//! before they start.
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    query::{Mode, RotationFilter, ScheduleMatch},
+    schedules::{Schedule, Schedules},
+};
+
+/// Rotations to be reminded of. Every given filter must match, see `RotationFilter`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertRule {
+    /// Title of the alert, the mode of the rotation if unset.
+    pub name: Option<String>,
+    /// Any of these modes, or every mode if empty.
+    pub mode: Vec<Mode>,
+    /// Name or id of the battle rule, e.g. "Clam Blitz".
+    pub rule: Option<String>,
+    /// Name or id of one of the stages.
+    pub stage: Option<String>,
+    /// Name or id of one of the weapons of a Salmon Run shift, e.g. "Grizzco" or "Random".
+    pub weapon: Option<String>,
+    /// How long before the start of the rotation the alert fires.
+    pub minutes_before: u32,
+}
+
+/// A rotation matching an `AlertRule`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alert<'a> {
+    pub rule: &'a AlertRule,
+    pub rotation: ScheduleMatch<'a>,
+}
+
+impl AlertRule {
+    pub fn filter(&self) -> RotationFilter<'_> {
+        RotationFilter {
+            mode: &self.mode,
+            rule: self.rule.as_deref(),
+            stage: self.stage.as_deref(),
+            weapon: self.weapon.as_deref(),
+        }
+    }
+
+    /// A rule without any filter would match every rotation.
+    pub fn is_empty(&self) -> bool {
+        self.filter().is_empty()
+    }
+
+    pub fn matches(&self, rotation: &ScheduleMatch) -> bool {
+        self.filter().matches(rotation)
+    }
+
+    /// When the alert for `rotation` fires.
+    pub fn fires_at(&self, rotation: &ScheduleMatch) -> DateTime<Utc> {
+        rotation.get_start_time() - Duration::minutes(self.minutes_before.into())
+    }
+}
+
+impl Alert<'_> {
+    pub fn title(&self) -> &str {
+        self.rule
+            .name
+            .as_deref()
+            .unwrap_or_else(|| self.rotation.mode.name())
+    }
+}
+
+impl Schedules {
+    /// The alerts of `rules` firing after `since` and until `until`, sorted by the time they
+    /// fire at. Checking consecutive intervals fires each alert once.
+    ///
+    /// Rotations that have ended by `until` are left out, e.g. after the computer was asleep.
+    pub fn alerts<'a>(
+        &'a self,
+        rules: &'a [AlertRule],
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Vec<Alert<'a>> {
+        let rotations = self.find(|rotation| rotation.get_end_time() > until);
+        let mut res: Vec<Alert> = rules
+            .iter()
+            .flat_map(|rule| {
+                rotations
+                    .iter()
+                    .filter(|rotation| {
+                        let fires_at = rule.fires_at(rotation);
+                        since < fires_at && fires_at <= until && rule.matches(rotation)
+                    })
+                    .map(move |rotation| Alert {
+                        rule,
+                        rotation: *rotation,
+                    })
+            })
+            .collect();
+        res.sort_by_key(|alert| alert.rule.fires_at(&alert.rotation));
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use crate::data::{
+        query::{Mode, Rotation, ScheduleMatch},
+        schedules::{BattleSchedule, CoopSchedule, Schedules},
+        test_util::{get_test_battle, get_test_big_run, name_id},
+    };
+
+    use super::AlertRule;
+
+    /// Clam Blitz then Splat Zones in X Battle, and a Big Run, all starting at 12:00.
+    fn get_test_schedules() -> Schedules {
+        let battle = get_test_battle();
+        let start_time = battle.start_time;
+        Schedules {
+            x_battle: vec![
+                BattleSchedule {
+                    rule: name_id("Clam Blitz"),
+                    ..battle.clone()
+                },
+                BattleSchedule {
+                    start_time: start_time + Duration::hours(2),
+                    end_time: start_time + Duration::hours(4),
+                    stages: vec![name_id("Museum d'Alfonsino"), name_id("Eeltail Alley")],
+                    ..battle
+                },
+            ],
+            work_big_run: vec![CoopSchedule {
+                start_time,
+                end_time: start_time + Duration::days(2),
+                ..get_test_big_run()
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_alert_rule_matches() {
+        let schedules = get_test_schedules();
+        let clam_blitz = ScheduleMatch {
+            mode: Mode::XBattle,
+            rotation: Rotation::Battle(&schedules.x_battle[0]),
+        };
+        let big_run = ScheduleMatch {
+            mode: Mode::BigRun,
+            rotation: Rotation::Work(&schedules.work_big_run[0]),
+        };
+
+        let rule = AlertRule {
+            mode: vec![Mode::XBattle],
+            rule: Some("clam blitz".to_string()),
+            ..Default::default()
+        };
+        assert!(rule.matches(&clam_blitz));
+        assert!(!rule.matches(&big_run));
+
+        let rule = AlertRule {
+            weapon: Some("splattershot".to_string()),
+            ..Default::default()
+        };
+        assert!(rule.matches(&big_run));
+        assert!(!rule.matches(&clam_blitz));
+
+        assert!(AlertRule::default().is_empty());
+        assert!(AlertRule::default().matches(&clam_blitz));
+    }
+
+    #[test]
+    fn test_schedules_alerts() {
+        let schedules = get_test_schedules();
+        let start_time = Utc.with_ymd_and_hms(2025, 11, 8, 12, 0, 0).unwrap();
+        let rules = [
+            AlertRule {
+                name: Some("Clams".to_string()),
+                mode: vec![Mode::XBattle],
+                rule: Some("Clam Blitz".to_string()),
+                minutes_before: 10,
+                ..Default::default()
+            },
+            AlertRule {
+                stage: Some("Eeltail Alley".to_string()),
+                ..Default::default()
+            },
+            AlertRule {
+                mode: vec![Mode::BigRun],
+                minutes_before: 60,
+                ..Default::default()
+            },
+        ];
+
+        let alerts = schedules.alerts(
+            &rules,
+            start_time - Duration::hours(2),
+            start_time - Duration::minutes(10),
+        );
+        let titles: Vec<&str> = alerts.iter().map(|alert| alert.title()).collect();
+        assert_eq!(titles, vec!["Big Run", "Clams"]);
+
+        // Each alert fires once
+        let alerts = schedules.alerts(&rules, start_time - Duration::minutes(10), start_time);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(
+            alerts[0].rotation.rotation,
+            Rotation::Battle(&schedules.x_battle[0])
+        );
+
+        // After a long sleep, the first X Battle has ended and is left out
+        let alerts = schedules.alerts(
+            &rules,
+            start_time - Duration::hours(2),
+            start_time + Duration::hours(2) + Duration::minutes(1),
+        );
+        assert_eq!(alerts.len(), 2);
+        assert_eq!(alerts[0].title(), "Big Run");
+        assert_eq!(
+            alerts[1].rotation.rotation,
+            Rotation::Battle(&schedules.x_battle[1])
+        );
+    }
+}
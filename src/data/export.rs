@@ -0,0 +1,140 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    query::{Mode, Rotation, ScheduleMatch},
+    schedules::{NameID, Schedule},
+};
+
+/// Version of the exported schema. It's increased whenever a field is removed, renamed, or
+/// changes meaning; new fields can be added without a new version.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A list of rotations, as exported in a single JSON or YAML document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Export {
+    pub version: u32,
+    pub rotations: Vec<ExportedRotation>,
+}
+
+/// A rotation, in the public schema of `SCHEMA_VERSION`.
+///
+/// Names are translated, the ids are the same in every language. Times are in RFC 3339.
+///
+/// * `rule`: `None` for Salmon Run shifts.
+/// * `event`: The Challenge event, only set for Challenges.
+/// * `boss`: The King Salmonid, if known.
+/// * `weapons`: Supplied weapons of Salmon Run shifts, empty for battles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportedRotation {
+    pub mode: Mode,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub rule: Option<NameID>,
+    pub stages: Vec<NameID>,
+    pub event: Option<NameID>,
+    pub boss: Option<NameID>,
+    pub weapons: Vec<NameID>,
+}
+
+/// An `ExportedRotation` that can be read on its own, e.g. a line of NDJSON.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionedRotation {
+    pub version: u32,
+    #[serde(flatten)]
+    pub rotation: ExportedRotation,
+}
+
+impl From<&ScheduleMatch<'_>> for ExportedRotation {
+    fn from(value: &ScheduleMatch<'_>) -> Self {
+        let (event, boss, weapons) = match value.rotation {
+            Rotation::Battle(_) => (None, None, vec![]),
+            Rotation::Challenge(league, _) => (Some(league.event_name.clone()), None, vec![]),
+            Rotation::Work(shift) => (None, shift.boss.clone(), shift.weapons.clone()),
+        };
+        ExportedRotation {
+            mode: value.mode,
+            start_time: value.get_start_time(),
+            end_time: value.get_end_time(),
+            rule: value.rule().cloned(),
+            stages: value.stages().to_vec(),
+            event,
+            boss,
+            weapons,
+        }
+    }
+}
+
+impl Export {
+    pub fn new(rotations: &[ScheduleMatch<'_>]) -> Self {
+        Export {
+            version: SCHEMA_VERSION,
+            rotations: rotations.iter().map(|rotation| rotation.into()).collect(),
+        }
+    }
+}
+
+impl From<ExportedRotation> for VersionedRotation {
+    fn from(value: ExportedRotation) -> Self {
+        VersionedRotation {
+            version: SCHEMA_VERSION,
+            rotation: value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use crate::data::{
+        query::{Mode, Rotation, ScheduleMatch},
+        schedules::{BattleSchedule, NameID},
+        test_util::get_test_battle,
+    };
+
+    use super::{Export, ExportedRotation, VersionedRotation};
+
+    #[test]
+    fn test_export_schema() {
+        // Translated names are exported with the original ids
+        let battle = BattleSchedule {
+            stages: vec![NameID {
+                name: "温泉花大峡谷".to_string(),
+                id: "VnNTdGFnZS0x".to_string(),
+            }],
+            rule: NameID {
+                name: "ガチエリア".to_string(),
+                id: "VnNSdWxlLTE=".to_string(),
+            },
+            ..get_test_battle()
+        };
+        let rotation = ScheduleMatch {
+            mode: Mode::XBattle,
+            rotation: Rotation::Battle(&battle),
+        };
+        let export = serde_json::to_value(Export::new(&[rotation])).unwrap();
+        assert_eq!(
+            export,
+            json!({
+                "version": 1,
+                "rotations": [{
+                    "mode": "x-battle",
+                    "start_time": "2025-11-08T12:00:00Z",
+                    "end_time": "2025-11-08T14:00:00Z",
+                    "rule": {"name": "ガチエリア", "id": "VnNSdWxlLTE="},
+                    "stages": [{"name": "温泉花大峡谷", "id": "VnNTdGFnZS0x"}],
+                    "event": null,
+                    "boss": null,
+                    "weapons": [],
+                }],
+            })
+        );
+
+        let line = serde_json::to_value(VersionedRotation::from(ExportedRotation::from(&rotation)))
+            .unwrap();
+        assert_eq!(line["version"], 1);
+        assert_eq!(line["mode"], "x-battle");
+        assert_eq!(line["stages"], export["rotations"][0]["stages"]);
+    }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use super::{
+    raw_data,
+    schedules::{FestColor, FestState},
+    translation::{Dictionary, Translatable, TranslationDictionary, festival_team_key},
+};
+
+#[derive(
+    Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, Copy, Display, EnumString,
+)]
+#[strum(ascii_case_insensitive)]
+pub enum Region {
+    #[default]
+    US,
+    EU,
+    JP,
+    AP,
+}
+
+pub type Festivals = HashMap<Region, Vec<Festival>>;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct Festival {
+    pub id: String,
+    pub title: String,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub state: FestState,
+    pub teams: Vec<FestivalTeam>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct FestivalTeam {
+    pub name: String,
+    pub color: FestColor,
+    pub result: Option<FestivalTeamResult>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct FestivalTeamResult {
+    pub is_winner: bool,
+    pub vote_ratio: Option<f64>,
+    pub horagai_ratio: Option<f64>,
+    pub regular_contribution_ratio: Option<f64>,
+    pub challenge_contribution_ratio: Option<f64>,
+    pub tricolor_contribution_ratio: Option<f64>,
+}
+
+impl From<&raw_data::FestRecord> for Festival {
+    fn from(value: &raw_data::FestRecord) -> Self {
+        Festival {
+            id: value.splatoon3ink_id.clone(),
+            title: value.title.clone(),
+            start_time: value.start_time,
+            end_time: value.end_time,
+            state: (&value.state).into(),
+            teams: value
+                .teams
+                .iter()
+                .map(|team| FestivalTeam {
+                    name: team.team_name.clone(),
+                    color: team.color.into(),
+                    result: team.result.as_ref().map(|result| FestivalTeamResult {
+                        is_winner: result.is_winner,
+                        vote_ratio: result.vote_ratio,
+                        horagai_ratio: result.horagai_ratio,
+                        regular_contribution_ratio: result.regular_contribution_ratio,
+                        challenge_contribution_ratio: result.challenge_contribution_ratio,
+                        tricolor_contribution_ratio: result.tricolor_contribution_ratio,
+                    }),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Festival {
+    /// The winning team, if the results are announced.
+    pub fn winner(&self) -> Option<&FestivalTeam> {
+        self.teams
+            .iter()
+            .find(|team| team.result.as_ref().is_some_and(|result| result.is_winner))
+    }
+}
+
+impl Translatable for Festival {
+    fn translate(&self, dict: &TranslationDictionary) -> Self {
+        Festival {
+            title: dict.lookup(&self.id).unwrap_or(self.title.clone()),
+            teams: self
+                .teams
+                .iter()
+                .enumerate()
+                .map(|(index, team)| FestivalTeam {
+                    name: dict
+                        .lookup(&festival_team_key(&self.id, index))
+                        .unwrap_or(team.name.clone()),
+                    ..team.clone()
+                })
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+
+    use crate::data::{
+        schedules::{FestColor, FestState},
+        translation::{
+            FlattenedTranslationDictionary, Translatable, TranslationDictionary, festival_team_key,
+        },
+    };
+
+    use super::{Festival, FestivalTeam, FestivalTeamResult, Region};
+
+    fn get_test_team(name: &str, is_winner: bool) -> FestivalTeam {
+        FestivalTeam {
+            name: name.to_string(),
+            color: FestColor { r: 0, g: 0, b: 0 },
+            result: Some(FestivalTeamResult {
+                is_winner,
+                vote_ratio: Some(0.3),
+                horagai_ratio: Some(0.3),
+                regular_contribution_ratio: Some(0.3),
+                challenge_contribution_ratio: Some(0.3),
+                tricolor_contribution_ratio: Some(0.3),
+            }),
+        }
+    }
+
+    fn get_test_festival() -> Festival {
+        Festival {
+            id: "JUEA-00001".to_string(),
+            title: "test fest".to_string(),
+            start_time: Utc.with_ymd_and_hms(2022, 11, 12, 0, 0, 0).unwrap(),
+            end_time: Utc.with_ymd_and_hms(2022, 11, 14, 0, 0, 0).unwrap(),
+            state: FestState::Closed,
+            teams: vec![
+                get_test_team("team 1", false),
+                get_test_team("team 2", true),
+                get_test_team("team 3", false),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_festival_winner() {
+        let festival = get_test_festival();
+        assert_eq!(festival.winner().unwrap().name, "team 2");
+    }
+
+    #[test]
+    fn test_translate_festival() {
+        let dict: TranslationDictionary = FlattenedTranslationDictionary::from([
+            ("JUEA-00001".to_string(), "translated fest".to_string()),
+            (
+                festival_team_key("JUEA-00001", 1),
+                "translated team 2".to_string(),
+            ),
+        ])
+        .into();
+        let translated = get_test_festival().translate(&dict);
+        assert_eq!(translated.title, "translated fest");
+        assert_eq!(translated.teams[0].name, "team 1");
+        assert_eq!(translated.teams[1].name, "translated team 2");
+    }
+
+    #[test]
+    fn test_parse_region() {
+        assert_eq!("eu".parse::<Region>().unwrap(), Region::EU);
+        assert_eq!("JP".parse::<Region>().unwrap(), Region::JP);
+        assert!("nowhere".parse::<Region>().is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    raw_data,
+    schedules::NameID,
+    translation::{Translatable, TranslationDictionary},
+};
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct GearShop {
+    pub pickup_brand: Option<PickupBrand>,
+    pub limited_gears: Vec<GearSale>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct PickupBrand {
+    pub brand: NameID,
+    pub usual_power: Option<NameID>,
+    pub sale_end_time: DateTime<Utc>,
+    pub gears: Vec<GearSale>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct GearSale {
+    pub name: NameID,
+    pub brand: NameID,
+    pub primary_power: NameID,
+    pub additional_slots: usize,
+    pub price: u32,
+    pub sale_end_time: DateTime<Utc>,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct CoopReward {
+    pub monthly_gear: NameID,
+}
+
+impl From<raw_data::RawCoopData> for CoopReward {
+    fn from(value: raw_data::RawCoopData) -> Self {
+        let monthly_gear = value.data.coop_result.monthly_gear;
+        CoopReward {
+            monthly_gear: NameID {
+                name: monthly_gear.name,
+                id: monthly_gear.id.unwrap_or_default(),
+            },
+        }
+    }
+}
+
+impl From<&raw_data::GearPower> for NameID {
+    fn from(value: &raw_data::GearPower) -> Self {
+        NameID {
+            name: value.name.clone(),
+            id: value.id.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&raw_data::GearSale> for GearSale {
+    fn from(value: &raw_data::GearSale) -> Self {
+        GearSale {
+            name: NameID {
+                name: value.gear.name.clone(),
+                id: value.gear.id.clone().unwrap_or_default(),
+            },
+            brand: (&value.gear.brand).into(),
+            primary_power: (&value.gear.primary_gear_power).into(),
+            additional_slots: value.gear.additional_gear_powers.len(),
+            price: value.price,
+            sale_end_time: value.sale_end_time,
+        }
+    }
+}
+
+impl From<raw_data::RawGearData> for GearShop {
+    fn from(value: raw_data::RawGearData) -> Self {
+        let gesotown = value.data.gesotown;
+        let pickup_brand = &gesotown.pickup_brand;
+        GearShop {
+            pickup_brand: Some(PickupBrand {
+                brand: NameID {
+                    name: pickup_brand.brand.name.clone(),
+                    id: pickup_brand.brand.id.clone(),
+                },
+                usual_power: pickup_brand
+                    .brand
+                    .usual_gear_power
+                    .as_ref()
+                    .map(|power| power.into()),
+                sale_end_time: pickup_brand.sale_end_time,
+                gears: pickup_brand
+                    .brand_gears
+                    .iter()
+                    .map(|gear| gear.into())
+                    .collect(),
+            }),
+            limited_gears: gesotown
+                .limited_gears
+                .iter()
+                .map(|gear| gear.into())
+                .collect(),
+        }
+    }
+}
+
+impl Translatable for GearSale {
+    fn translate(&self, dict: &TranslationDictionary) -> Self {
+        GearSale {
+            name: self.name.translate(dict),
+            brand: self.brand.translate(dict),
+            primary_power: self.primary_power.translate(dict),
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+
+    use crate::data::{
+        schedules::NameID,
+        translation::{FlattenedTranslationDictionary, Translatable, TranslationDictionary},
+    };
+
+    use super::GearSale;
+
+    #[test]
+    fn test_translate_gear_sale() {
+        let gear = GearSale {
+            name: NameID {
+                name: "Annaki Beret".to_string(),
+                id: "e1b6b7e8f2b4c3d9".to_string(),
+            },
+            brand: NameID {
+                name: "Annaki".to_string(),
+                id: "QnJhbmQtNg==".to_string(),
+            },
+            primary_power: NameID {
+                name: "Ink Resistance Up".to_string(),
+                id: "4b6d3b4a0eb5a6d2".to_string(),
+            },
+            additional_slots: 2,
+            price: 3750,
+            sale_end_time: Utc.with_ymd_and_hms(2025, 12, 14, 4, 0, 0).unwrap(),
+        };
+        let dict: TranslationDictionary = FlattenedTranslationDictionary::from([
+            (
+                "e1b6b7e8f2b4c3d9".to_string(),
+                "translated gear".to_string(),
+            ),
+            ("QnJhbmQtNg==".to_string(), "translated brand".to_string()),
+        ])
+        .into();
+
+        let translated = gear.translate(&dict);
+        assert_eq!(translated.name.name, "translated gear");
+        assert_eq!(translated.brand.name, "translated brand");
+        // Falls back to the original name if there's no translation
+        assert_eq!(translated.primary_power.name, "Ink Resistance Up");
+        assert_eq!(translated.price, 3750);
+    }
+}
@@ -0,0 +1,195 @@
+//! iCalendar (RFC 5545) export of rotations, e.g. to import Challenges or Big Runs into a shared
+//! calendar.
+use chrono::{DateTime, Utc};
+
+use super::{
+    query::{Rotation, ScheduleMatch},
+    schedules::{NameID, Schedule},
+};
+
+/// Lines longer than this many bytes are folded.
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Write `rotations` as a calendar with one VEVENT each. `dtstamp` is the time the calendar is
+/// created at.
+///
+/// UIDs are derived from the mode and the start time, so that importing an updated calendar
+/// replaces the events instead of duplicating them.
+pub fn to_ics(rotations: &[ScheduleMatch<'_>], dtstamp: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!(
+            "PRODID:-//{}//{} {}//EN",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        ),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for rotation in rotations {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event_uid(rotation)));
+        lines.push(format!("DTSTAMP:{}", format_time(dtstamp)));
+        lines.push(format!(
+            "DTSTART:{}",
+            format_time(rotation.get_start_time())
+        ));
+        lines.push(format!("DTEND:{}", format_time(rotation.get_end_time())));
+        lines.push(format!("SUMMARY:{}", escape_text(&event_summary(rotation))));
+        lines.push(format!(
+            "LOCATION:{}",
+            escape_text(&join_names(rotation.stages()))
+        ));
+        let description = event_description(rotation);
+        if !description.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", escape_text(&description)));
+        }
+        lines.push(format!("CATEGORIES:{}", rotation.mode));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+/// Stable across exports, as there's only one rotation at a time in each mode.
+fn event_uid(rotation: &ScheduleMatch) -> String {
+    format!(
+        "{}-{}@{}",
+        rotation.mode,
+        format_time(rotation.get_start_time()),
+        env!("CARGO_PKG_NAME")
+    )
+}
+
+/// Only made of translated names. The mode isn't translated by splatoon3.ink, it's in CATEGORIES.
+fn event_summary(rotation: &ScheduleMatch) -> String {
+    match rotation.rotation {
+        Rotation::Battle(battle) => battle.rule.name.clone(),
+        Rotation::Challenge(league, _) => {
+            format!("{} ({})", league.event_name.name, league.rule.name)
+        }
+        Rotation::Work(shift) => shift.stage.name.clone(),
+    }
+}
+
+fn event_description(rotation: &ScheduleMatch) -> String {
+    match rotation.rotation {
+        Rotation::Battle(_) => String::new(),
+        Rotation::Challenge(league, _) => [league.desc.as_str(), league.details.as_str()]
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Rotation::Work(shift) => {
+            let weapons = format!("Weapons: {}", join_names(&shift.weapons));
+            match &shift.boss {
+                Some(boss) => format!("Boss: {}\n{weapons}", boss.name),
+                None => weapons,
+            }
+        }
+    }
+}
+
+fn join_names(names: &[NameID]) -> String {
+    names
+        .iter()
+        .map(|name| name.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Split lines longer than `MAX_LINE_LENGTH` bytes, continuation lines start with a space.
+/// Multi-byte characters, such as in Japanese names, are never split.
+fn fold_line(line: &str) -> String {
+    let mut res = String::with_capacity(line.len());
+    let mut line_length = 0;
+    for char in line.chars() {
+        if line_length + char.len_utf8() > MAX_LINE_LENGTH {
+            res.push_str("\r\n ");
+            line_length = 1;
+        }
+        res.push(char);
+        line_length += char.len_utf8();
+    }
+    res
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+
+    use crate::data::{
+        query::{Mode, Rotation, ScheduleMatch},
+        test_util::{get_test_battle, get_test_big_run},
+    };
+
+    use super::{MAX_LINE_LENGTH, escape_text, fold_line, to_ics};
+
+    #[test]
+    fn test_to_ics() {
+        let shift = get_test_big_run();
+        let rotation = ScheduleMatch {
+            mode: Mode::BigRun,
+            rotation: Rotation::Work(&shift),
+        };
+        let ics = to_ics(
+            &[rotation],
+            Utc.with_ymd_and_hms(2025, 11, 7, 12, 0, 0).unwrap(),
+        );
+
+        let lines: Vec<&str> = ics.split_terminator("\r\n").collect();
+        assert_eq!(lines.first(), Some(&"BEGIN:VCALENDAR"));
+        assert_eq!(lines.last(), Some(&"END:VCALENDAR"));
+        assert!(lines.contains(&"UID:big-run-20251108T000000Z@idacast"));
+        assert!(lines.contains(&"DTSTAMP:20251107T120000Z"));
+        assert!(lines.contains(&"DTSTART:20251108T000000Z"));
+        assert!(lines.contains(&"DTEND:20251110T000000Z"));
+        assert!(lines.contains(&"SUMMARY:Spawning Grounds"));
+        assert!(lines.contains(&"DESCRIPTION:Boss: Cohozuna\\nWeapons: Splattershot\\, Random"));
+        assert!(lines.contains(&"CATEGORIES:big-run"));
+        assert!(ics.ends_with("\r\n"));
+
+        // The untranslated mode is only in the category
+        let battle = get_test_battle();
+        let rotation = ScheduleMatch {
+            mode: Mode::XBattle,
+            rotation: Rotation::Battle(&battle),
+        };
+        let ics = to_ics(&[rotation], Utc::now());
+        assert!(ics.contains("\r\nSUMMARY:Splat Zones\r\n"));
+        assert!(ics.contains("\r\nCATEGORIES:x-battle\r\n"));
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a, b; c\\d\ne"), "a\\, b\\; c\\\\d\\ne");
+    }
+
+    #[test]
+    fn test_fold_line() {
+        assert_eq!(fold_line("SUMMARY:short"), "SUMMARY:short");
+
+        let line = format!("LOCATION:{}", "ガチエリア".repeat(10));
+        let folded = fold_line(&line);
+        assert!(
+            folded
+                .split("\r\n")
+                .all(|line| line.len() <= MAX_LINE_LENGTH)
+        );
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
@@ -1,26 +1,64 @@
-use crate::data::schedules::{BattleSchedule, CoopSchedule, LeagueSchedule, Schedule};
-use chrono::Local;
-use futures::join;
+use crate::data::festivals::{Festival, Festivals, Region};
+use crate::data::gear::{CoopReward, GearShop};
+use crate::data::schedules::{BattleSchedule, CoopSchedule, CurrentFest, LeagueSchedule};
 use schedules::Schedules;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use serde_json::Value;
-use std::{cmp::min, fmt::Display};
+use std::{collections::HashMap, fmt::Display};
 use translation::Translatable;
 
-use color_eyre::{Report, Result};
-use reqwest::Url;
-pub mod raw_data;
+use color_eyre::{Report, Result, eyre::eyre};
+use source::{DataSource, Document, Fetched, Validators};
+pub mod alerts;
+pub mod export;
+pub mod festivals;
+pub mod gear;
+pub mod ics;
+pub mod query;
+mod raw_data;
 pub mod schedules;
+pub mod source;
+#[cfg(test)]
+mod test_util;
 pub mod translation;
 
 impl std::error::Error for DataError {}
 
-static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
+pub(crate) static APP_USER_AGENT: &str =
+    concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DataError {
     ObjectNonExist(String),
     TranslationError(String),
+    /// The data source can't be connected to, e.g. there's no network connection.
+    NetworkUnreachable {
+        url: String,
+    },
+    Timeout {
+        url: String,
+    },
+    HttpStatus {
+        url: String,
+        status: u16,
+    },
+    /// The document doesn't have the expected structure, `path` points to the offending value.
+    SchemaMismatch {
+        document: String,
+        path: String,
+        message: String,
+    },
+    CacheCorruption {
+        cache: String,
+        message: String,
+    },
+    /// The cache can't be opened, e.g. it's locked by another process.
+    CacheUnavailable {
+        cache: String,
+        message: String,
+    },
+    /// There's no translation for the language.
+    UnsupportedLocale(String),
 }
 
 impl Display for DataError {
@@ -32,36 +70,103 @@ impl Display for DataError {
             DataError::ObjectNonExist(object) => {
                 write!(f, "Object {} should exist in the data", object)
             }
+            DataError::NetworkUnreachable { url } => {
+                write!(f, "Unable to connect to {}", url)
+            }
+            DataError::Timeout { url } => write!(f, "Request to {} timed out", url),
+            DataError::HttpStatus { url, status } => {
+                write!(f, "Request to {} failed with HTTP status {}", url, status)
+            }
+            DataError::SchemaMismatch {
+                document,
+                path,
+                message,
+            } => {
+                write!(
+                    f,
+                    "Unexpected data in {} at {}: {}",
+                    document, path, message
+                )
+            }
+            DataError::CacheCorruption { cache, message } => {
+                write!(f, "Cache {} is corrupted: {}", cache, message)
+            }
+            DataError::CacheUnavailable { cache, message } => {
+                write!(f, "Cache {} can't be opened: {}", cache, message)
+            }
+            DataError::UnsupportedLocale(lang) => {
+                write!(f, "Language {} is not supported", lang)
+            }
         }
     }
 }
-async fn fetch_data() -> Result<raw_data::RawData> {
-    let client = reqwest::Client::builder()
-        .user_agent(APP_USER_AGENT)
-        .build()?;
-
-    let res: String = client
-        .get("https://splatoon3.ink/data/schedules.json")
-        .send()
-        .await?
-        .text()
-        .await?;
-    let res: raw_data::RawData = serde_json::from_str(&res)?;
 
-    Ok(res)
+/// Parse `document`, reporting the JSON path of the value that doesn't match the schema.
+fn parse_json<'de, T: Deserialize<'de>>(
+    document: &str,
+    deserializer: impl serde::Deserializer<'de, Error = serde_json::Error>,
+) -> Result<T> {
+    serde_path_to_error::deserialize(deserializer).map_err(|err| {
+        Report::new(DataError::SchemaMismatch {
+            document: document.to_string(),
+            path: err.path().to_string(),
+            message: err.inner().to_string(),
+        })
+    })
+}
+
+async fn fetch_json<T: DeserializeOwned>(source: &dyn DataSource, path: &str) -> Result<T> {
+    let res: String = source.fetch(path).await?;
+    parse_json(path, &mut serde_json::Deserializer::from_str(&res))
+}
+
+async fn fetch_gear(source: &dyn DataSource) -> Result<raw_data::RawGearData> {
+    fetch_json(source, "gear.json").await
+}
+
+async fn fetch_coop(source: &dyn DataSource) -> Result<raw_data::RawCoopData> {
+    fetch_json(source, "coop.json").await
 }
 
-async fn fetch_translation(lang: String) -> Result<translation::FlattenedTranslationDictionary> {
-    let base_url: Url = Url::parse("https://splatoon3.ink/data/locale/")?;
-    let joined_url: Url = base_url.join(&format!("{}.json", lang))?;
+async fn fetch_festivals(
+    source: &dyn DataSource,
+) -> Result<HashMap<Region, raw_data::RawFestivalRegion>> {
+    fetch_json(source, "festivals.json").await
+}
 
-    let client = reqwest::Client::builder()
-        .user_agent(APP_USER_AGENT)
-        .build()?;
-    let res: String = client.get(joined_url).send().await?.text().await?;
+/// The dictionary to translate the documents to `lang`, `None` if they don't need to be
+/// translated, as they're in English. Fetch it once to translate several documents, e.g. with
+/// `get_gear_shop` and `get_festivals`.
+pub async fn get_dictionary(
+    source: &dyn DataSource,
+    lang: Option<String>,
+) -> Result<Option<translation::TranslationDictionary>> {
+    match lang {
+        Some(langcode) if langcode != "en-US" => {
+            Ok(Some(fetch_translation(source, langcode).await?))
+        }
+        _ => Ok(None),
+    }
+}
+
+async fn fetch_translation(
+    source: &dyn DataSource,
+    lang: String,
+) -> Result<translation::TranslationDictionary> {
+    let path = format!("locale/{}.json", lang);
+    let res: String =
+        source
+            .fetch(&path)
+            .await
+            .map_err(|err| match err.downcast_ref::<DataError>() {
+                Some(DataError::HttpStatus { status: 404, .. }) => {
+                    Report::new(DataError::UnsupportedLocale(lang.clone()))
+                }
+                _ => err,
+            })?;
 
     // Need to sanitize data, workaround for https://github.com/misenhower/splatoon3.ink/issues/94
-    let mut res: Value = serde_json::from_str(&res)?;
+    let mut res: Value = parse_json(&path, &mut serde_json::Deserializer::from_str(&res))?;
     let rules = match res.get_mut("rules") {
         Some(rules) => rules,
         None => {
@@ -78,21 +183,23 @@ async fn fetch_translation(lang: String) -> Result<translation::FlattenedTransla
     }
 
     // After sanitization, continue parsing
-    let res: translation::TranslationData = serde_json::from_value(res)?;
-    let res: translation::FlattenedTranslationDictionary = res.into();
+    let res: translation::TranslationData = parse_json(&path, res)?;
+    let res: translation::TranslationDictionary = res.into();
 
     Ok(res)
 }
 
 pub fn translate_schedules(
     mut schedules: Schedules,
-    dict: &translation::FlattenedTranslationDictionary,
+    dict: &translation::TranslationDictionary,
 ) -> Result<Schedules> {
     translate_battles(
         &mut schedules.regular,
         &mut schedules.anarchy_open,
         &mut schedules.anarchy_series,
         &mut schedules.x_battle,
+        &mut schedules.fest_open,
+        &mut schedules.fest_pro,
         dict,
     );
     translate_work(
@@ -102,16 +209,33 @@ pub fn translate_schedules(
         dict,
     );
     translate_league(&mut schedules.league, dict);
+    if let Some(fest) = &mut schedules.current_fest {
+        translate_fest(fest, dict);
+    }
+    schedules.vs_stages.iter_mut().for_each(|stage| {
+        *stage = stage.translate(dict);
+    });
 
     Ok(schedules)
 }
 
+fn translate_fest(fest: &mut CurrentFest, dict: &translation::TranslationDictionary) {
+    fest.tricolor_stage = fest
+        .tricolor_stage
+        .take()
+        .map(|stage| stage.translate(dict));
+}
+
 fn translate_league(
     league_challenges: &mut [LeagueSchedule],
-    dict: &translation::FlattenedTranslationDictionary,
+    dict: &translation::TranslationDictionary,
 ) {
     league_challenges.iter_mut().for_each(|schedule| {
-        schedule.event_name = schedule.event_name.translate(dict);
+        if let Ok(event) = dict.lookup_event(&schedule.event_name.id) {
+            schedule.event_name.name = event.name.clone();
+            schedule.desc = event.desc.clone();
+            schedule.details = event.regulation.clone();
+        }
         schedule.stages.iter_mut().for_each(|stage| {
             *stage = stage.translate(dict);
         });
@@ -123,7 +247,7 @@ fn translate_work(
     regular: &mut [CoopSchedule],
     big_run: &mut [CoopSchedule],
     team_contest: &mut [CoopSchedule],
-    dict: &translation::FlattenedTranslationDictionary,
+    dict: &translation::TranslationDictionary,
 ) {
     let translate = |schedules: &mut [CoopSchedule]| {
         schedules.iter_mut().for_each(|schedule| {
@@ -144,7 +268,9 @@ fn translate_battles(
     anarchy_open: &mut [BattleSchedule],
     anarchy_series: &mut [BattleSchedule],
     x_battle: &mut [BattleSchedule],
-    dict: &translation::FlattenedTranslationDictionary,
+    fest_open: &mut [BattleSchedule],
+    fest_pro: &mut [BattleSchedule],
+    dict: &translation::TranslationDictionary,
 ) {
     let translate = |schedules: &mut [BattleSchedule]| {
         schedules.iter_mut().for_each(|schedule| {
@@ -160,86 +286,170 @@ fn translate_battles(
     translate(anarchy_open);
     translate(anarchy_series);
     translate(x_battle);
+    translate(fest_open);
+    translate(fest_pro);
 }
 
-pub async fn get_schedules(lang: Option<String>) -> Result<schedules::Schedules> {
-    let raw_schedules_fut = fetch_data();
+/// Fetch the schedules from `source`, translated to `lang` if it's set.
+pub async fn get_schedules(
+    source: &dyn DataSource,
+    lang: Option<String>,
+) -> Result<schedules::Schedules> {
+    match get_schedules_if_modified(source, lang, &Validators::default()).await? {
+        Fetched::Modified(document) => Ok(document.value),
+        Fetched::NotModified { .. } => Err(eyre!(
+            "schedules.json is not modified, but no validators were sent"
+        )),
+    }
+}
 
-    match lang {
-        None => Ok(raw_schedules_fut.await?.into()),
-        Some(langcode) => {
-            if langcode == "en-US" {
-                return Ok(raw_schedules_fut.await?.into());
-            }
+/// Same as `get_schedules`, but nothing is downloaded or translated if schedules.json hasn't
+/// changed since it was fetched with `validators`.
+pub async fn get_schedules_if_modified(
+    source: &dyn DataSource,
+    lang: Option<String>,
+    validators: &Validators,
+) -> Result<Fetched<schedules::Schedules>> {
+    let document = match source
+        .fetch_if_modified("schedules.json", validators)
+        .await?
+    {
+        Fetched::Modified(document) => document,
+        Fetched::NotModified { max_age } => return Ok(Fetched::NotModified { max_age }),
+    };
+    let raw_schedules: raw_data::RawData = parse_json(
+        "schedules.json",
+        &mut serde_json::Deserializer::from_str(&document.value),
+    )?;
+    // Only fetched when the schedules have changed, so that a conditional request is enough
+    // when nothing's new.
+    let schedules: Schedules = match get_dictionary(source, lang).await? {
+        Some(dict) => translate_schedules(raw_schedules.into(), &dict)?,
+        None => raw_schedules.into(),
+    };
 
-            let dict_fut = fetch_translation(langcode);
-            let (raw_schedules, dict) = join!(raw_schedules_fut, dict_fut);
+    Ok(Fetched::Modified(Document {
+        value: schedules,
+        validators: document.validators,
+        max_age: document.max_age,
+    }))
+}
 
-            let translated: Schedules = translate_schedules(raw_schedules?.into(), &dict?)?;
-            Ok(translated)
-        }
+pub fn translate_gear_shop(
+    mut gear_shop: GearShop,
+    dict: &translation::TranslationDictionary,
+) -> GearShop {
+    if let Some(pickup_brand) = &mut gear_shop.pickup_brand {
+        pickup_brand.brand = pickup_brand.brand.translate(dict);
+        pickup_brand.usual_power = pickup_brand
+            .usual_power
+            .take()
+            .map(|power| power.translate(dict));
+        pickup_brand.gears.iter_mut().for_each(|gear| {
+            *gear = gear.translate(dict);
+        });
     }
+    gear_shop.limited_gears.iter_mut().for_each(|gear| {
+        *gear = gear.translate(dict);
+    });
+
+    gear_shop
 }
 
-pub fn filter_schedules<T: Schedule>(
-    schedules: &[T],
-    count: usize,
-    shift: Option<usize>,
-) -> Option<&[T]> {
-    let mut start: Option<usize> = None;
-    let time_now = Local::now();
-    for (index, schedule) in schedules.iter().enumerate() {
-        if schedule.get_end_time() >= time_now {
-            // If a schedule start in the future, display the first one.
-            start = Some(index);
-            break;
-        }
-    }
-    match start {
-        Some(start) => {
-            let end = min(
-                start
-                    .saturating_add(count)
-                    .saturating_add(shift.unwrap_or(0)),
-                schedules.len(),
-            );
-            let start = min(start.saturating_add(shift.unwrap_or(0)), end);
-            Some(&schedules[start..end])
-        }
-        None => None,
-    }
+/// Fetch the shop from `source`, translated with `dict` if it's set, see `get_dictionary`.
+pub async fn get_gear_shop(
+    source: &dyn DataSource,
+    dict: Option<&translation::TranslationDictionary>,
+) -> Result<GearShop> {
+    let gear_shop: GearShop = fetch_gear(source).await?.into();
+    Ok(match dict {
+        Some(dict) => translate_gear_shop(gear_shop, dict),
+        None => gear_shop,
+    })
+}
+
+pub async fn get_coop_reward(
+    source: &dyn DataSource,
+    dict: Option<&translation::TranslationDictionary>,
+) -> Result<CoopReward> {
+    let coop_reward: CoopReward = fetch_coop(source).await?.into();
+    Ok(match dict {
+        Some(dict) => CoopReward {
+            monthly_gear: coop_reward.monthly_gear.translate(dict),
+        },
+        None => coop_reward,
+    })
+}
+
+pub async fn get_festivals(
+    source: &dyn DataSource,
+    dict: Option<&translation::TranslationDictionary>,
+) -> Result<Festivals> {
+    Ok(fetch_festivals(source)
+        .await?
+        .into_iter()
+        .map(|(region, raw_region)| {
+            let festivals: Vec<Festival> = raw_region
+                .data
+                .fest_records
+                .nodes
+                .iter()
+                .map(|record| {
+                    let festival: Festival = record.into();
+                    match dict {
+                        Some(dict) => festival.translate(dict),
+                        None => festival,
+                    }
+                })
+                .collect();
+            (region, festivals)
+        })
+        .collect())
 }
 
 #[cfg(test)]
 mod test {
     use chrono::{Duration, Utc};
+    use color_eyre::{Result, eyre::eyre};
+    use futures::{FutureExt, future::BoxFuture};
 
     use crate::data::{
-        fetch_translation, get_schedules, schedules::Schedules,
-        translation::FlattenedTranslationDictionary,
+        DataError, fetch_translation, get_dictionary, get_schedules, get_schedules_if_modified,
+        schedules::Schedules,
+        source::{DEFAULT_BASE_URL, DataSource, Fetched, HttpSource, MemorySource, Validators},
+        translation::{
+            EventTranslationContent, EventTranslationMap, FlattenedTranslationDictionary,
+            TranslationDictionary,
+        },
     };
 
     use super::{
-        filter_schedules,
-        schedules::{BattleSchedule, NameID},
+        schedules::{CurrentFest, FestState, LeagueSchedule, NameID},
+        translate_schedules,
     };
 
     #[tokio::test]
     async fn test_get_schedules_online() {
-        let _schedules: Schedules = get_schedules(None).await.unwrap();
+        let source = HttpSource::new(DEFAULT_BASE_URL).unwrap();
+        let _schedules: Schedules = get_schedules(&source, None).await.unwrap();
         dbg!(&_schedules);
     }
 
     #[tokio::test]
     async fn test_get_dictionary_online() {
-        let dict: FlattenedTranslationDictionary =
-            fetch_translation("zh-CN".to_owned()).await.unwrap();
+        let source = HttpSource::new(DEFAULT_BASE_URL).unwrap();
+        let dict: TranslationDictionary = fetch_translation(&source, "zh-CN".to_owned())
+            .await
+            .unwrap();
+        let dict: FlattenedTranslationDictionary = dict.names;
         assert_eq!(dict.get("VnNTdGFnZS0y").unwrap(), "鳗鲶区");
         assert_eq!(dict.get("VnNSdWxlLTM=").unwrap(), "真格鱼虎对战");
         assert!(!dict.contains_key("non-existent id"));
 
-        let dict: FlattenedTranslationDictionary =
-            fetch_translation("ja-JP".to_owned()).await.unwrap();
+        let dict: TranslationDictionary = fetch_translation(&source, "ja-JP".to_owned())
+            .await
+            .unwrap();
+        let dict: FlattenedTranslationDictionary = dict.names;
         assert_eq!(dict.get("VnNTdGFnZS0y").unwrap(), "ゴンズイ地区");
         assert_eq!(dict.get("VnNSdWxlLTM=").unwrap(), "ガチホコバトル");
         assert!(!dict.contains_key("non-existent id"));
@@ -247,20 +457,108 @@ mod test {
 
     #[tokio::test]
     async fn test_get_schedules_online_with_translation() {
-        let _schedules_translated: Schedules =
-            get_schedules(Some("zh-CN".to_owned())).await.unwrap();
+        let source = HttpSource::new(DEFAULT_BASE_URL).unwrap();
+        let _schedules_translated: Schedules = get_schedules(&source, Some("zh-CN".to_owned()))
+            .await
+            .unwrap();
         dbg!(&_schedules_translated);
     }
 
-    fn get_test_schedule(time_now: chrono::DateTime<Utc>, i: i64) -> BattleSchedule {
-        BattleSchedule {
-            start_time: time_now - Duration::minutes(90) + Duration::hours(i * 2),
-            end_time: time_now + Duration::minutes(30) + Duration::hours(i * 2),
-            stages: get_test_stages(i.try_into().unwrap()),
-            rule: get_test_rule(),
+    #[tokio::test]
+    async fn test_get_schedules_from_memory() {
+        let source = MemorySource::from([(
+            "schedules.json",
+            include_str!("../../resources/samples/bigrun/schedules.json"),
+        )]);
+        let schedules: Schedules = get_schedules(&source, None).await.unwrap();
+        assert_eq!(schedules.work_big_run.len(), 1);
+
+        // The translation is not available in memory
+        assert!(
+            get_schedules(&source, Some("zh-CN".to_owned()))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_dictionary() {
+        // English documents don't need a dictionary, nothing is fetched
+        let source = MemorySource::default();
+        assert!(get_dictionary(&source, None).await.unwrap().is_none());
+        assert!(
+            get_dictionary(&source, Some("en-US".to_owned()))
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(
+            get_dictionary(&source, Some("zh-CN".to_owned()))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_schema_mismatch_path() {
+        let source = MemorySource::from([(
+            "schedules.json",
+            r#"{"data": {"regularSchedules": {"nodes": 42}}}"#,
+        )]);
+        let err = get_schedules(&source, None).await.unwrap_err();
+        match err.downcast_ref::<DataError>() {
+            Some(DataError::SchemaMismatch { document, path, .. }) => {
+                assert_eq!(document, "schedules.json");
+                assert_eq!(path, "data.regularSchedules.nodes");
+            }
+            _ => panic!("Expected a schema mismatch, got {err}"),
+        }
+    }
+
+    /// Reports every document as not modified, as long as validators are sent.
+    struct NotModifiedSource;
+
+    impl DataSource for NotModifiedSource {
+        fn fetch<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+            async move { Err(eyre!("{path} should not be downloaded")) }.boxed()
+        }
+
+        fn fetch_if_modified<'a>(
+            &'a self,
+            path: &'a str,
+            validators: &'a Validators,
+        ) -> BoxFuture<'a, Result<Fetched<String>>> {
+            async move {
+                if validators.etag.is_none() {
+                    return Err(eyre!("{path} should be requested with validators"));
+                }
+                Ok(Fetched::NotModified {
+                    max_age: Some(Duration::seconds(300)),
+                })
+            }
+            .boxed()
         }
     }
 
+    #[tokio::test]
+    async fn test_get_schedules_not_modified() {
+        let validators = Validators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        // The translation isn't fetched either
+        let fetched =
+            get_schedules_if_modified(&NotModifiedSource, Some("zh-CN".to_owned()), &validators)
+                .await
+                .unwrap();
+        assert_eq!(
+            fetched,
+            Fetched::NotModified {
+                max_age: Some(Duration::seconds(300))
+            }
+        );
+    }
+
     fn get_test_stages(start: isize) -> Vec<NameID> {
         let mut sample_stages = Vec::new();
         for i in start..start + 2 {
@@ -280,36 +578,82 @@ mod test {
     }
 
     #[test]
-    fn test_filter_schedule_by_time() {
+    fn test_translate_tricolor_stage() {
         let time_now = Utc::now();
+        let schedules = Schedules {
+            current_fest: Some(CurrentFest {
+                id: "test_fest".to_string(),
+                title: "test fest".to_string(),
+                start_time: time_now - Duration::days(1),
+                midterm_time: time_now,
+                end_time: time_now + Duration::days(1),
+                state: FestState::SecondHalf,
+                teams: Vec::new(),
+                tricolor_stage: Some(NameID {
+                    name: "Mahi-Mahi Resort".to_string(),
+                    id: "VnNTdGFnZS0xMg==".to_string(),
+                }),
+                is_tricolor_active: true,
+            }),
+            ..Default::default()
+        };
+        let dict: TranslationDictionary = FlattenedTranslationDictionary::from([(
+            "VnNTdGFnZS0xMg==".to_string(),
+            "translated stage".to_string(),
+        )])
+        .into();
+
+        let translated = translate_schedules(schedules, &dict).unwrap();
+        assert_eq!(
+            translated
+                .current_fest
+                .unwrap()
+                .tricolor_stage
+                .unwrap()
+                .name,
+            "translated stage"
+        );
+    }
 
-        let mut sample_schedules = Vec::new();
-        for i in -2..14 {
-            // Usually 12 in the api (http://splatoon3.ink/data/schedules.json)
-            sample_schedules.push(get_test_schedule(time_now, i));
-        }
-
-        let filtered = filter_schedules(&sample_schedules, 3, None).unwrap();
-
-        assert_eq!(filtered.len(), 3);
-        assert_ne!(filtered[0], get_test_schedule(time_now, -1));
-        assert_eq!(filtered[0], get_test_schedule(time_now, 0));
-        assert_eq!(filtered[1], get_test_schedule(time_now, 1));
-        assert_eq!(filtered[2], get_test_schedule(time_now, 2));
-        assert_ne!(filtered[2], get_test_schedule(time_now, 3));
-
-        let filtered_alt = filter_schedules(&sample_schedules, usize::MAX, None).unwrap();
-        assert_eq!(filtered_alt.len(), 14);
-
-        let filtered_shift = filter_schedules(&sample_schedules, 25, Some(1)).unwrap();
-        assert_eq!(filtered_shift.len(), 13);
-        assert_eq!(filtered_shift[0], get_test_schedule(time_now, 1));
-
-        let filtered_shift = filter_schedules(&sample_schedules, 3, Some(1)).unwrap();
-        assert_eq!(filtered_shift.len(), 3);
-        assert_eq!(filtered_shift[0], get_test_schedule(time_now, 1));
-
-        let filtered_empty = filter_schedules(&sample_schedules, 3, Some(64)).unwrap();
-        assert!(filtered_empty.is_empty());
+    #[test]
+    fn test_translate_league_event() {
+        let event_id = "TGVhZ3VlTWF0Y2hFdmVudC1TcGVjaWFsUnVzaF9VbHRyYVNob3Q=";
+        let schedules = Schedules {
+            league: vec![LeagueSchedule {
+                event_name: NameID {
+                    name: "Too Many Trizookas!".to_string(),
+                    id: event_id.to_string(),
+                },
+                desc: "A high-powered Trizooka battle!".to_string(),
+                details: "Can you defeat a Trizooka user with a Trizooka of your own?!".to_string(),
+                stages: get_test_stages(0),
+                rule: get_test_rule(),
+                time_periods: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        let dict = TranslationDictionary {
+            names: FlattenedTranslationDictionary::from([(
+                "test_0".to_string(),
+                "translated stage".to_string(),
+            )]),
+            events: EventTranslationMap::from([(
+                event_id.to_string(),
+                EventTranslationContent {
+                    name: "translated name".to_string(),
+                    desc: "translated desc".to_string(),
+                    regulation: "translated regulation".to_string(),
+                },
+            )]),
+        };
+
+        let translated = translate_schedules(schedules, &dict).unwrap();
+        let event = &translated.league[0];
+        assert_eq!(event.event_name.name, "translated name");
+        assert_eq!(event.event_name.id, event_id);
+        assert_eq!(event.desc, "translated desc");
+        assert_eq!(event.details, "translated regulation");
+        assert_eq!(event.stages[0].name, "translated stage");
+        assert_eq!(event.stages[1].name, "test stage 1");
     }
 }
@@ -0,0 +1,380 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+use super::schedules::{
+    BattleSchedule, CoopSchedule, LeagueSchedule, NameID, Schedule, Schedules, TimePeriod,
+};
+
+/// Each list of rotations in `Schedules`.
+#[derive(
+    Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Clone, Copy, Display, EnumString, EnumIter,
+)]
+#[serde(rename_all = "kebab-case")]
+#[strum(serialize_all = "kebab-case", ascii_case_insensitive)]
+pub enum Mode {
+    Regular,
+    #[strum(to_string = "anarchy-series", serialize = "series")]
+    AnarchySeries,
+    #[strum(to_string = "anarchy-open", serialize = "open")]
+    AnarchyOpen,
+    #[strum(to_string = "x-battle", serialize = "x")]
+    XBattle,
+    Challenge,
+    FestOpen,
+    FestPro,
+    #[strum(to_string = "work", serialize = "salmon-run")]
+    Work,
+    BigRun,
+    TeamContest,
+}
+
+impl Mode {
+    /// Human readable name, `Display` gives the identifier used on the command line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Regular => "Regular Battle",
+            Mode::AnarchySeries => "Anarchy Series",
+            Mode::AnarchyOpen => "Anarchy Open",
+            Mode::XBattle => "X Battle",
+            Mode::Challenge => "Challenge",
+            Mode::FestOpen => "Splatfest Open",
+            Mode::FestPro => "Splatfest Pro",
+            Mode::Work => "Salmon Run",
+            Mode::BigRun => "Big Run",
+            Mode::TeamContest => "Team Contest",
+        }
+    }
+}
+
+/// Keep the rotations matching every given filter: names match a part of the name regardless of
+/// the case, ids match exactly, see `NameID::matches`. Shared by the alerts and the exports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RotationFilter<'a> {
+    /// Any of these modes, or every mode if empty.
+    pub mode: &'a [Mode],
+    /// Name or id of the battle rule.
+    pub rule: Option<&'a str>,
+    /// Name or id of one of the stages.
+    pub stage: Option<&'a str>,
+    /// Name or id of one of the weapons of a Salmon Run shift.
+    pub weapon: Option<&'a str>,
+}
+
+/// A rotation, along with the list of `Schedules` it's from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleMatch<'a> {
+    pub mode: Mode,
+    pub rotation: Rotation<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation<'a> {
+    Battle(&'a BattleSchedule),
+    Work(&'a CoopSchedule),
+    /// Challenges are played in several time periods, each of them is a separate rotation.
+    Challenge(&'a LeagueSchedule, &'a TimePeriod),
+}
+
+impl<'a> ScheduleMatch<'a> {
+    pub fn stages(&self) -> &'a [NameID] {
+        match self.rotation {
+            Rotation::Battle(schedule) => &schedule.stages,
+            Rotation::Work(schedule) => std::slice::from_ref(&schedule.stage),
+            Rotation::Challenge(schedule, _) => &schedule.stages,
+        }
+    }
+
+    /// The battle rule, Salmon Run shifts don't have one.
+    pub fn rule(&self) -> Option<&'a NameID> {
+        match self.rotation {
+            Rotation::Battle(schedule) => Some(&schedule.rule),
+            Rotation::Work(_) => None,
+            Rotation::Challenge(schedule, _) => Some(&schedule.rule),
+        }
+    }
+
+    pub fn has_stage(&self, stage_id: &str) -> bool {
+        self.stages().iter().any(|stage| stage.id == stage_id)
+    }
+
+    pub fn is_ongoing(&self, time: DateTime<Utc>) -> bool {
+        self.get_start_time() <= time && time < self.get_end_time()
+    }
+
+    /// What's played and where, e.g. "Splat Zones: Scorch Gorge, Eeltail Alley".
+    pub fn details(&self) -> String {
+        let stages = join_names(self.stages());
+        match self.rotation {
+            Rotation::Battle(battle) => format!("{}: {stages}", battle.rule.name),
+            Rotation::Challenge(league, _) => {
+                format!(
+                    "{} ({}): {stages}",
+                    league.event_name.name, league.rule.name
+                )
+            }
+            Rotation::Work(shift) => {
+                let weapons = join_names(&shift.weapons);
+                match &shift.boss {
+                    Some(boss) => format!("{stages} vs {}: {weapons}", boss.name),
+                    None => format!("{stages}: {weapons}"),
+                }
+            }
+        }
+    }
+}
+
+fn join_names(entries: &[NameID]) -> String {
+    entries
+        .iter()
+        .map(|entry| entry.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+impl RotationFilter<'_> {
+    /// A filter without any criteria matches every rotation.
+    pub fn is_empty(&self) -> bool {
+        self.mode.is_empty() && self.rule.is_none() && self.stage.is_none() && self.weapon.is_none()
+    }
+
+    pub fn matches(&self, rotation: &ScheduleMatch) -> bool {
+        (self.mode.is_empty() || self.mode.contains(&rotation.mode))
+            && self
+                .rule
+                .is_none_or(|rule| rotation.rule().is_some_and(|name| name.matches(rule)))
+            && self
+                .stage
+                .is_none_or(|stage| rotation.stages().iter().any(|name| name.matches(stage)))
+            && self.weapon.is_none_or(|weapon| match rotation.rotation {
+                Rotation::Work(shift) => shift.weapons.iter().any(|name| name.matches(weapon)),
+                _ => false,
+            })
+    }
+}
+
+impl Schedule for ScheduleMatch<'_> {
+    fn get_start_time(&self) -> DateTime<Utc> {
+        match self.rotation {
+            Rotation::Battle(schedule) => schedule.start_time,
+            Rotation::Work(schedule) => schedule.start_time,
+            Rotation::Challenge(_, time_period) => time_period.start_time,
+        }
+    }
+
+    fn get_end_time(&self) -> DateTime<Utc> {
+        match self.rotation {
+            Rotation::Battle(schedule) => schedule.end_time,
+            Rotation::Work(schedule) => schedule.end_time,
+            Rotation::Challenge(_, time_period) => time_period.end_time,
+        }
+    }
+}
+
+impl Schedules {
+    /// Every rotation of `mode`, in the order of the data.
+    pub fn rotations(&self, mode: Mode) -> Vec<ScheduleMatch<'_>> {
+        fn battles(schedules: &[BattleSchedule]) -> Vec<Rotation<'_>> {
+            schedules.iter().map(Rotation::Battle).collect()
+        }
+        fn work(schedules: &[CoopSchedule]) -> Vec<Rotation<'_>> {
+            schedules.iter().map(Rotation::Work).collect()
+        }
+
+        let rotations = match mode {
+            Mode::Regular => battles(&self.regular),
+            Mode::AnarchySeries => battles(&self.anarchy_series),
+            Mode::AnarchyOpen => battles(&self.anarchy_open),
+            Mode::XBattle => battles(&self.x_battle),
+            Mode::Challenge => self
+                .league
+                .iter()
+                .flat_map(|schedule| {
+                    schedule
+                        .time_periods
+                        .iter()
+                        .map(move |time_period| Rotation::Challenge(schedule, time_period))
+                })
+                .collect(),
+            Mode::FestOpen => battles(&self.fest_open),
+            Mode::FestPro => battles(&self.fest_pro),
+            Mode::Work => work(&self.work_regular),
+            Mode::BigRun => work(&self.work_big_run),
+            Mode::TeamContest => work(&self.work_team_contest),
+        };
+        rotations
+            .into_iter()
+            .map(|rotation| ScheduleMatch { mode, rotation })
+            .collect()
+    }
+
+    /// What's being played at `time`, in the order of `Mode`.
+    pub fn at(&self, time: DateTime<Utc>) -> Vec<ScheduleMatch<'_>> {
+        Mode::iter()
+            .flat_map(|mode| self.rotations(mode))
+            .filter(|schedule| schedule.is_ongoing(time))
+            .collect()
+    }
+
+    pub fn current(&self) -> Vec<ScheduleMatch<'_>> {
+        self.at(Utc::now())
+    }
+
+    /// Rotations of `mode` that haven't ended at `time`, starting with the ongoing one.
+    pub fn upcoming(&self, mode: Mode, time: DateTime<Utc>) -> Vec<ScheduleMatch<'_>> {
+        let mut res: Vec<ScheduleMatch> = self
+            .rotations(mode)
+            .into_iter()
+            .filter(|schedule| schedule.get_end_time() > time)
+            .collect();
+        res.sort_by_key(|schedule| schedule.get_start_time());
+        res
+    }
+
+    /// The ongoing rotation of `mode` and the ones after it, `n` at most.
+    pub fn next_n(&self, mode: Mode, n: usize) -> Vec<ScheduleMatch<'_>> {
+        let mut res = self.upcoming(mode, Utc::now());
+        res.truncate(n);
+        res
+    }
+
+    /// Rotations of every mode matching `predicate`, sorted by start time.
+    pub fn find(&self, predicate: impl Fn(&ScheduleMatch) -> bool) -> Vec<ScheduleMatch<'_>> {
+        let mut res: Vec<ScheduleMatch> = Mode::iter()
+            .flat_map(|mode| self.rotations(mode))
+            .filter(|schedule| predicate(schedule))
+            .collect();
+        // The sort is stable, rotations starting at the same time stay in the order of `Mode`
+        res.sort_by_key(|schedule| schedule.get_start_time());
+        res
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use chrono::{Duration, TimeZone, Utc};
+
+    use crate::data::{
+        raw_data::RawData,
+        schedules::{Schedule, Schedules},
+        test_util::{get_test_battle, get_test_big_run},
+    };
+
+    use super::{Mode, Rotation, ScheduleMatch};
+
+    fn get_sample() -> Schedules {
+        let raw: RawData = serde_json::from_str(include_str!(
+            "../../resources/samples/teamcontest/schedules.json"
+        ))
+        .unwrap();
+        raw.into()
+    }
+
+    #[test]
+    fn test_mode_from_str() {
+        assert_eq!(Mode::from_str("x-battle"), Ok(Mode::XBattle));
+        assert_eq!(Mode::from_str("x"), Ok(Mode::XBattle));
+        assert_eq!(Mode::from_str("Big-Run"), Ok(Mode::BigRun));
+        assert_eq!(Mode::XBattle.to_string(), "x-battle");
+        assert_eq!(Mode::TeamContest.to_string(), "team-contest");
+        assert!(Mode::from_str("turf-war").is_err());
+    }
+
+    #[test]
+    fn test_schedules_at() {
+        let schedules = get_sample();
+        let time = Utc.with_ymd_and_hms(2025, 11, 8, 21, 0, 0).unwrap();
+        let res = schedules.at(time);
+
+        let modes: Vec<Mode> = res.iter().map(|schedule| schedule.mode).collect();
+        assert_eq!(
+            modes,
+            vec![
+                Mode::Regular,
+                Mode::AnarchySeries,
+                Mode::AnarchyOpen,
+                Mode::XBattle,
+                Mode::Work,
+                Mode::TeamContest,
+            ]
+        );
+        assert!(res.iter().all(|schedule| schedule.is_ongoing(time)));
+
+        // Nothing is known that far ahead
+        assert!(schedules.at(time + Duration::days(30)).is_empty());
+    }
+
+    #[test]
+    fn test_schedules_upcoming() {
+        let schedules = get_sample();
+        let time = Utc.with_ymd_and_hms(2025, 11, 8, 21, 0, 0).unwrap();
+
+        let res = schedules.upcoming(Mode::XBattle, time);
+        assert_eq!(res.len(), schedules.x_battle.len() - 4);
+        assert!(res[0].is_ongoing(time));
+        assert!(
+            res.windows(2)
+                .all(|pair| pair[0].get_end_time() <= pair[1].get_start_time())
+        );
+
+        let res = schedules.upcoming(
+            Mode::Challenge,
+            schedules.league[0].time_periods[0].end_time,
+        );
+        assert!(matches!(
+            res[0].rotation,
+            Rotation::Challenge(_, time_period) if *time_period == schedules.league[0].time_periods[1]
+        ));
+    }
+
+    #[test]
+    fn test_schedules_find() {
+        let schedules = get_sample();
+        let rule = &schedules.anarchy_open[0].rule;
+        let stage = &schedules.anarchy_open[0].stages[0];
+
+        let res = schedules
+            .find(|schedule| schedule.rule() == Some(rule) && schedule.has_stage(&stage.id));
+        assert!(!res.is_empty());
+        assert_eq!(
+            res[0].rotation,
+            Rotation::Battle(&schedules.anarchy_open[0])
+        );
+        assert!(
+            res.windows(2)
+                .all(|pair| pair[0].get_start_time() <= pair[1].get_start_time())
+        );
+
+        // Salmon Run stages can be found too, but there's no rule
+        let work_stage = &schedules.work_regular[0].stage;
+        let res = schedules.find(|schedule| schedule.has_stage(&work_stage.id));
+        assert_eq!(res[0].mode, Mode::Work);
+        assert_eq!(res[0].rule(), None);
+    }
+
+    #[test]
+    fn test_schedule_match_details() {
+        let battle = get_test_battle();
+        let rotation = ScheduleMatch {
+            mode: Mode::XBattle,
+            rotation: Rotation::Battle(&battle),
+        };
+        assert_eq!(
+            rotation.details(),
+            "Splat Zones: Scorch Gorge, Eeltail Alley"
+        );
+
+        let shift = get_test_big_run();
+        let rotation = ScheduleMatch {
+            mode: Mode::BigRun,
+            rotation: Rotation::Work(&shift),
+        };
+        assert_eq!(
+            rotation.details(),
+            "Spawning Grounds vs Cohozuna: Splattershot, Random"
+        );
+    }
+}
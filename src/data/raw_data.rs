@@ -1,6 +1,6 @@
 /// Raw JSON data fetched from splatoon3.ink
 use chrono::{self, Utc};
-use serde::{self, Deserialize, Serialize};
+use serde::{self, Deserialize, Deserializer, Serialize, de::DeserializeOwned};
 
 #[derive(Deserialize)]
 /// The root of the splatoon3.ink json is a data object, so we need to wrap it.
@@ -12,21 +12,27 @@ pub(super) struct RawData {
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-/// Data for stages, currently only supports regular, ranked and X.
-/// TODO: event, fest, and grizzco may be supported in the future.
+/// Data for stages: regular, ranked, X, event, fest and grizzco.
 ///
 /// * `regular_schedules`:
 /// * `bankara_schedules`:
 /// * `x_schedules`:
-/// * `vs_stages`:
+/// * `event_schedules`:
+/// * `fest_schedules`:
+/// * `coop_grouping_schedule`:
+/// * `current_fest`: `null` unless a Splatfest is announced or running, dropped if it can't be
+///   parsed.
+/// * `vs_stages`: The current stage pool.
 pub(super) struct Data {
     pub regular_schedules: ScheduleContainer<MatchNode>,
     pub bankara_schedules: ScheduleContainer<MatchNodeBankara>,
     pub x_schedules: ScheduleContainer<MatchNode>,
     pub event_schedules: ScheduleContainer<MatchNodeLeague>,
-    // fest_schedules: MatchSchedules,
+    pub fest_schedules: ScheduleContainer<MatchNodeFest>,
     pub coop_grouping_schedule: CoopGroupingSchedule,
-    // current_fest: MatchSchedules,
+    #[serde(default)]
+    pub current_fest: LenientNode<CurrentFest>,
+    pub vs_stages: ScheduleContainer<VsStage>,
 }
 
 #[derive(Deserialize)]
@@ -34,6 +40,7 @@ pub(super) struct Data {
 pub(super) struct CoopGroupingSchedule {
     pub regular_schedules: ScheduleContainer<CoopNode>,
     pub big_run_schedules: ScheduleContainer<CoopNode>,
+    pub team_contest_schedules: ScheduleContainer<CoopNode>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -62,22 +69,124 @@ pub(super) struct NameID {
     pub id: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct VsStage {
+    pub vs_stage_id: u32,
+    pub name: String,
+    pub id: String,
+}
+
 /// Schedules are usually in a node container with a vector of single schedules.
 ///
+/// Nodes are parsed one by one, so that a node with an unexpected shape is skipped instead of
+/// failing the whole document.
+///
 /// * `nodes`:
+/// * `skipped`: Nodes that couldn't be parsed.
 pub(super) struct ScheduleContainer<T> {
     pub nodes: Vec<T>,
+    pub skipped: Vec<SkippedNode>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+/// A node left out of its `ScheduleContainer`.
+///
+/// * `index`: Position of the node in the container.
+/// * `path`: Where the error happened inside the node, empty if it's the node itself.
+/// * `message`:
+pub(super) struct SkippedNode {
+    pub index: usize,
+    pub path: String,
+    pub message: String,
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for ScheduleContainer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Nodes {
+            nodes: Vec<serde_json::Value>,
+        }
+
+        let mut res = ScheduleContainer {
+            nodes: vec![],
+            skipped: vec![],
+        };
+        for (index, node) in Nodes::deserialize(deserializer)?
+            .nodes
+            .into_iter()
+            .enumerate()
+        {
+            match parse_node(node, index) {
+                Ok(node) => res.nodes.push(node),
+                Err(skipped) => res.skipped.push(skipped),
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// A single node, that is dropped instead of failing the whole document when it has an
+/// unexpected shape.
+///
+/// * `node`: `None` if it's `null`, missing, or couldn't be parsed.
+/// * `skipped`: Why it couldn't be parsed, its `index` is always 0.
+pub(super) struct LenientNode<T> {
+    pub node: Option<T>,
+    pub skipped: Option<SkippedNode>,
+}
+
+impl<T> Default for LenientNode<T> {
+    fn default() -> Self {
+        LenientNode {
+            node: None,
+            skipped: None,
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for LenientNode<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(
+            match parse_node(serde_json::Value::deserialize(deserializer)?, 0) {
+                Ok(node) => LenientNode {
+                    node,
+                    skipped: None,
+                },
+                Err(skipped) => LenientNode {
+                    node: None,
+                    skipped: Some(skipped),
+                },
+            },
+        )
+    }
+}
+
+fn parse_node<T: DeserializeOwned>(
+    node: serde_json::Value,
+    index: usize,
+) -> Result<T, SkippedNode> {
+    serde_path_to_error::deserialize(node).map_err(|err| SkippedNode {
+        index,
+        path: err.path().to_string(),
+        message: err.inner().to_string(),
+    })
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
+/// The match setting is `null` while a Splatfest is running, as the regular and X battles are
+/// replaced by the fest battles.
+///
+/// * `start_time`:
+/// * `end_time`:
+/// * `match_setting`:
 pub(super) struct MatchNode {
     #[serde(alias = "startTime")]
     pub start_time: chrono::DateTime<Utc>,
     #[serde(alias = "endTime")]
     pub end_time: chrono::DateTime<Utc>,
     #[serde(alias = "regularMatchSetting", alias = "xMatchSetting")]
-    pub match_setting: MatchSetting,
+    pub match_setting: Option<MatchSetting>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -86,14 +195,14 @@ pub(super) struct MatchNode {
 ///
 /// * `start_time`:
 /// * `end_time`:
-/// * `match_settings`:
+/// * `match_settings`: `null` while a Splatfest is running.
 pub(super) struct MatchNodeBankara {
     #[serde(alias = "startTime")]
     pub start_time: chrono::DateTime<Utc>,
     #[serde(alias = "endTime")]
     pub end_time: chrono::DateTime<Utc>,
     #[serde(alias = "bankaraMatchSettings")]
-    pub match_settings: Vec<BankaraMatchSetting>,
+    pub match_settings: Option<Vec<BankaraMatchSetting>>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -108,6 +217,8 @@ pub(super) struct MatchSetting {
 pub(super) enum BankaraMode {
     Open,
     Challenge,
+    #[serde(untagged)]
+    Unknown(String),
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -118,6 +229,88 @@ pub(super) struct BankaraMatchSetting {
     pub bankara_mode: BankaraMode,
 }
 
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+/// Similar to `MatchNodeBankara`, there are two match settings: Open and Pro.
+///
+/// * `start_time`:
+/// * `end_time`:
+/// * `match_settings`: `null` unless a Splatfest is running.
+pub(super) struct MatchNodeFest {
+    #[serde(alias = "startTime")]
+    pub start_time: chrono::DateTime<Utc>,
+    #[serde(alias = "endTime")]
+    pub end_time: chrono::DateTime<Utc>,
+    #[serde(alias = "festMatchSettings")]
+    pub match_settings: Option<Vec<FestMatchSetting>>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "UPPERCASE")]
+pub(super) enum FestMode {
+    /// Splatfest Battle (Open)
+    Regular,
+    /// Splatfest Battle (Pro)
+    Challenge,
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct FestMatchSetting {
+    #[serde(flatten)]
+    pub match_setting: MatchSetting,
+    pub fest_mode: FestMode,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CurrentFest {
+    pub id: String,
+    pub title: String,
+    pub start_time: chrono::DateTime<Utc>,
+    pub midterm_time: chrono::DateTime<Utc>,
+    pub end_time: chrono::DateTime<Utc>,
+    pub state: FestState,
+    pub teams: Vec<FestTeam>,
+    pub tricolor_stage: Option<NameID>,
+    #[serde(default)]
+    pub is_tricolor_active: bool,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub(super) enum FestState {
+    Scheduled,
+    FirstHalf,
+    SecondHalf,
+    Closed,
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// The team name is not always present in the schedules, fall back to the team's index in that
+/// case.
+///
+/// * `id`:
+/// * `team_name`:
+/// * `color`:
+pub(super) struct FestTeam {
+    pub id: String,
+    pub team_name: Option<String>,
+    pub color: FestColor,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone, Copy)]
+/// Color channels ranging from 0 to 1.
+pub(super) struct FestColor {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub(super) struct MatchNodeLeague {
@@ -142,6 +335,153 @@ pub(super) struct LeagueMatchEvent {
     pub regulation: String,
 }
 
+#[derive(Deserialize)]
+/// Gear data fetched from splatoon3.ink, also wrapped in a data object.
+///
+/// * `data`:
+pub(super) struct RawGearData {
+    pub data: GearData,
+}
+
+#[derive(Deserialize)]
+pub(super) struct GearData {
+    pub gesotown: Gesotown,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// SplatNet 3 shop
+///
+/// * `pickup_brand`: The daily drop
+/// * `limited_gears`: Limited-time gear
+pub(super) struct Gesotown {
+    pub pickup_brand: PickupBrand,
+    pub limited_gears: Vec<GearSale>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PickupBrand {
+    pub brand: Brand,
+    pub sale_end_time: chrono::DateTime<Utc>,
+    pub brand_gears: Vec<GearSale>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Brand {
+    pub name: String,
+    pub id: String,
+    pub usual_gear_power: Option<GearPower>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct GearSale {
+    pub sale_end_time: chrono::DateTime<Utc>,
+    pub price: u32,
+    pub gear: Gear,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct Gear {
+    pub name: String,
+    #[serde(alias = "__splatoon3ink_id")]
+    pub id: Option<String>,
+    pub primary_gear_power: GearPower,
+    pub additional_gear_powers: Vec<GearPower>,
+    pub brand: NameID,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+/// Gear abilities, the additional slots are "Unknown" abilities.
+///
+/// * `name`:
+/// * `id`:
+pub(super) struct GearPower {
+    pub name: String,
+    #[serde(alias = "__splatoon3ink_id")]
+    pub id: Option<String>,
+}
+
+#[derive(Deserialize)]
+/// Salmon Run data fetched from splatoon3.ink, also wrapped in a data object.
+///
+/// * `data`:
+pub(super) struct RawCoopData {
+    pub data: CoopData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct CoopData {
+    pub coop_result: CoopResult,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// * `monthly_gear`: The Grizzco reward gear of the current month
+pub(super) struct CoopResult {
+    pub monthly_gear: MonthlyGear,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub(super) struct MonthlyGear {
+    pub name: String,
+    #[serde(alias = "__splatoon3ink_id")]
+    pub id: Option<String>,
+}
+
+#[derive(Deserialize)]
+/// Splatfest records fetched from splatoon3.ink, one for each region (US, EU, JP and AP).
+///
+/// * `data`:
+pub(super) struct RawFestivalRegion {
+    pub data: FestRecordsData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct FestRecordsData {
+    pub fest_records: ScheduleContainer<FestRecord>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// A past or upcoming Splatfest.
+///
+/// * `splatoon3ink_id`: The id used by splatoon3.ink's translation data
+pub(super) struct FestRecord {
+    #[serde(rename = "__splatoon3ink_id")]
+    pub splatoon3ink_id: String,
+    pub title: String,
+    pub start_time: chrono::DateTime<Utc>,
+    pub end_time: chrono::DateTime<Utc>,
+    pub state: FestState,
+    pub teams: Vec<FestRecordTeam>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct FestRecordTeam {
+    pub team_name: String,
+    pub color: FestColor,
+    pub result: Option<FestTeamResult>,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+/// The ratios range from 0 to 1, `horagai` means conch shells.
+pub(super) struct FestTeamResult {
+    pub is_winner: bool,
+    pub vote_ratio: Option<f64>,
+    pub horagai_ratio: Option<f64>,
+    pub regular_contribution_ratio: Option<f64>,
+    pub challenge_contribution_ratio: Option<f64>,
+    pub tricolor_contribution_ratio: Option<f64>,
+}
+
 #[derive(Deserialize, Debug, PartialEq, Eq, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TimePeriod {
@@ -154,12 +494,14 @@ mod test {
     use chrono::{TimeZone, Utc};
 
     use crate::data::raw_data::{
-        BankaraMatchSetting, BankaraMode, MatchNodeBankara, RawData, TimePeriod,
+        BankaraMatchSetting, BankaraMode, MatchNodeBankara, RawCoopData, RawData, TimePeriod,
     };
 
     use super::{
-        CoopNode, CoopSetting, LeagueMatchEvent, LeagueMatchSetting, MatchNode, MatchNodeLeague,
-        MatchSetting, NameID,
+        CoopNode, CoopSetting, CurrentFest, FestColor, FestMode, FestRecord, FestState, FestTeam,
+        GearPower, GearSale, LeagueMatchEvent, LeagueMatchSetting, LenientNode, MatchNode,
+        MatchNodeFest, MatchNodeLeague, MatchSetting, NameID, ScheduleContainer, SkippedNode,
+        VsStage,
     };
 
     #[test]
@@ -170,7 +512,7 @@ mod test {
         let expedted_regular_match = MatchNode {
             start_time: Utc.with_ymd_and_hms(2025, 7, 8, 6, 0, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2025, 7, 8, 8, 0, 0).unwrap(),
-            match_setting: MatchSetting {
+            match_setting: Some(MatchSetting {
                 vs_stages: vec![
                     NameID {
                         name: "Museum d'Alfonsino".to_string(),
@@ -185,7 +527,7 @@ mod test {
                     name: "Turf War".to_string(),
                     id: "VnNSdWxlLTA=".to_string(),
                 },
-            },
+            }),
         };
 
         let parsed_regular_match: MatchNode = serde_json::from_str(regular_match_example).unwrap();
@@ -200,7 +542,7 @@ mod test {
         let expected_bankara_match: MatchNodeBankara = MatchNodeBankara {
             start_time: Utc.with_ymd_and_hms(2025, 7, 8, 6, 0, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2025, 7, 8, 8, 0, 0).unwrap(),
-            match_settings: vec![
+            match_settings: Some(vec![
                 BankaraMatchSetting {
                     match_setting: MatchSetting {
                         vs_stages: vec![
@@ -239,7 +581,7 @@ mod test {
                     },
                     bankara_mode: BankaraMode::Open,
                 },
-            ],
+            ]),
         };
         let parsed_bankara_match: MatchNodeBankara =
             serde_json::from_str(example_bankara_match).unwrap();
@@ -285,7 +627,7 @@ mod test {
         let expected_schedule = MatchNode {
             start_time: Utc.with_ymd_and_hms(2025, 7, 8, 6, 0, 0).unwrap(),
             end_time: Utc.with_ymd_and_hms(2025, 7, 8, 8, 0, 0).unwrap(),
-            match_setting: MatchSetting {
+            match_setting: Some(MatchSetting {
                 vs_stages: vec![
                     NameID {
                         name: "Barnacle & Dime".to_string(),
@@ -300,7 +642,7 @@ mod test {
                     name: "Splat Zones".to_string(),
                     id: "VnNSdWxlLTE=".to_string(),
                 },
-            },
+            }),
         };
         let parsed_schedule: MatchNode = serde_json::from_str(x_match_example).unwrap();
         assert_eq!(expected_schedule, parsed_schedule);
@@ -356,7 +698,7 @@ mod test {
             },
         };
 
-        let parsed: CoopNode = serde_json::from_str(&example_schedule).unwrap();
+        let parsed: CoopNode = serde_json::from_str(example_schedule).unwrap();
         assert_eq!(parsed, expected);
     }
 
@@ -454,4 +796,195 @@ mod test {
         let parsed_schedule: MatchNodeLeague = serde_json::from_str(example_schedule_node).unwrap();
         assert_eq!(parsed_schedule, expected);
     }
+
+    #[test]
+    fn test_deserialize_regular_match_during_fest() {
+        let example = r#"{"startTime":"2025-07-08T06:00:00Z","endTime":"2025-07-08T08:00:00Z","regularMatchSetting":null,"festMatchSettings":null}"#;
+        let parsed: MatchNode = serde_json::from_str(example).unwrap();
+        assert_eq!(parsed.match_setting, None);
+
+        let example_bankara = r#"{"startTime":"2025-07-08T06:00:00Z","endTime":"2025-07-08T08:00:00Z","bankaraMatchSettings":null,"festMatchSettings":null}"#;
+        let parsed: MatchNodeBankara = serde_json::from_str(example_bankara).unwrap();
+        assert_eq!(parsed.match_settings, None);
+    }
+
+    #[test]
+    fn test_deserialize_fest_match() {
+        let example = r#"{"startTime":"2025-07-19T00:00:00Z","endTime":"2025-07-19T02:00:00Z","festMatchSettings":[{"__isVsSetting":"FestMatchSetting","__typename":"FestMatchSetting","vsStages":[{"vsStageId":2,"name":"Eeltail Alley","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/stage_img/icon/low_resolution/898e1ae6c737a9d44552c7c81f9b710676492681525c514eadc68a6780aa52af_1.png"},"id":"VnNTdGFnZS0y"}],"vsRule":{"name":"Turf War","rule":"TURF_WAR","id":"VnNSdWxlLTA="},"festMode":"CHALLENGE"},{"__isVsSetting":"FestMatchSetting","__typename":"FestMatchSetting","vsStages":[{"vsStageId":11,"name":"Museum d'Alfonsino","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/stage_img/icon/low_resolution/b9d8cfa186d197a27e075600a107c99d9e21646d116730f0843e0fff0aaba7dd_1.png"},"id":"VnNTdGFnZS0xMQ=="}],"vsRule":{"name":"Turf War","rule":"TURF_WAR","id":"VnNSdWxlLTA="},"festMode":"REGULAR"}]}"#;
+        let parsed: MatchNodeFest = serde_json::from_str(example).unwrap();
+        let settings = parsed.match_settings.unwrap();
+        assert_eq!(settings.len(), 2);
+        assert_eq!(settings[0].fest_mode, FestMode::Challenge);
+        assert_eq!(settings[1].fest_mode, FestMode::Regular);
+        assert_eq!(
+            settings[1].match_setting.vs_stages,
+            vec![NameID {
+                name: "Museum d'Alfonsino".to_string(),
+                id: "VnNTdGFnZS0xMQ==".to_string(),
+            }]
+        );
+
+        let example_empty = r#"{"startTime":"2025-12-13T02:00:00Z","festMatchSettings":null,"endTime":"2025-12-13T04:00:00Z"}"#;
+        let parsed: MatchNodeFest = serde_json::from_str(example_empty).unwrap();
+        assert_eq!(parsed.match_settings, None);
+    }
+
+    #[test]
+    fn test_deserialize_current_fest() {
+        let example = r#"{"id":"RmVzdC1VUzpKVUVBLTAwMDI5","title":"Which is the best?","startTime":"2025-07-19T00:00:00Z","endTime":"2025-07-21T00:00:00Z","midtermTime":"2025-07-20T00:00:00Z","state":"SECOND_HALF","teams":[{"id":"RmVzdFRlYW0tVVM6SlVFQS0wMDAyOTox","teamName":"Shiver","color":{"a":1,"b":0.8,"g":0.2,"r":0.1},"myVoteState":null},{"id":"RmVzdFRlYW0tVVM6SlVFQS0wMDAyOToy","color":{"a":1,"b":0.1,"g":0.6,"r":1},"myVoteState":null},{"id":"RmVzdFRlYW0tVVM6SlVFQS0wMDAyOToz","teamName":"Big Man","color":{"a":1,"b":0.5,"g":0.5,"r":0.5},"myVoteState":null}],"tricolorStage":{"name":"Mahi-Mahi Resort","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/stage_img/icon/high_resolution/8273118c1ffe1bf6fe031c7d8c9795dab52632c9b76e8e9f01f644ac5ae0ccc0_0.png"},"id":"VnNTdGFnZS0xMg=="},"isTricolorActive":true}"#;
+        let parsed: CurrentFest = serde_json::from_str(example).unwrap();
+        assert_eq!(parsed.title, "Which is the best?");
+        assert_eq!(parsed.state, FestState::SecondHalf);
+        assert_eq!(
+            parsed.midterm_time,
+            Utc.with_ymd_and_hms(2025, 7, 20, 0, 0, 0).unwrap()
+        );
+        assert_eq!(parsed.teams.len(), 3);
+        assert_eq!(
+            parsed.teams[0],
+            FestTeam {
+                id: "RmVzdFRlYW0tVVM6SlVFQS0wMDAyOTox".to_string(),
+                team_name: Some("Shiver".to_string()),
+                color: FestColor {
+                    r: 0.1,
+                    g: 0.2,
+                    b: 0.8
+                },
+            }
+        );
+        assert_eq!(parsed.teams[1].team_name, None);
+        assert_eq!(
+            parsed.tricolor_stage,
+            Some(NameID {
+                name: "Mahi-Mahi Resort".to_string(),
+                id: "VnNTdGFnZS0xMg==".to_string(),
+            })
+        );
+        assert!(parsed.is_tricolor_active);
+    }
+
+    #[test]
+    fn test_deserialize_invalid_current_fest() {
+        let example = r#"{"id":"RmVzdC1VUzpKVUVBLTAwMDI5","title":"Which is the best?","startTime":"2025-07-19T00:00:00Z","endTime":"2025-07-21T00:00:00Z","midtermTime":"2025-07-20T00:00:00Z","state":"SECOND_HALF","teams":[{"id":"RmVzdFRlYW0tVVM6SlVFQS0wMDAyOTox","color":"blue"}],"tricolorStage":null,"isTricolorActive":false}"#;
+        let parsed: LenientNode<CurrentFest> = serde_json::from_str(example).unwrap();
+        assert!(parsed.node.is_none());
+        let skipped = parsed.skipped.unwrap();
+        assert_eq!(skipped.path, "teams[0].color");
+
+        let parsed: LenientNode<CurrentFest> = serde_json::from_str("null").unwrap();
+        assert!(parsed.node.is_none());
+        assert!(parsed.skipped.is_none());
+    }
+
+    #[test]
+    fn test_deserialize_vs_stage() {
+        let example = r#"{"vsStageId": 1, "originalImage": {"url": "https://splatoon3.ink/assets/splatnet/v3/stage_img/icon/high_resolution/35f9ca08ccc2bf759774ab2cb886567c117b9287875ca92fb590c1294ddcdc1e_0.png"}, "name": "Scorch Gorge", "stats": null, "id": "VnNTdGFnZS0x"}"#;
+        let parsed: VsStage = serde_json::from_str(example).unwrap();
+        assert_eq!(
+            parsed,
+            VsStage {
+                vs_stage_id: 1,
+                name: "Scorch Gorge".to_string(),
+                id: "VnNTdGFnZS0x".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_unknown_modes() {
+        let mode: BankaraMode = serde_json::from_str(r#""OPEN""#).unwrap();
+        assert_eq!(mode, BankaraMode::Open);
+        let mode: BankaraMode = serde_json::from_str(r#""TOURNAMENT""#).unwrap();
+        assert_eq!(mode, BankaraMode::Unknown("TOURNAMENT".to_string()));
+        let mode: FestMode = serde_json::from_str(r#""TRICOLOR""#).unwrap();
+        assert_eq!(mode, FestMode::Unknown("TRICOLOR".to_string()));
+        let state: FestState = serde_json::from_str(r#""POSTPONED""#).unwrap();
+        assert_eq!(state, FestState::Unknown("POSTPONED".to_string()));
+    }
+
+    #[test]
+    fn test_deserialize_container_skips_nodes() {
+        let example = r#"{"nodes": [
+            {"vsStageId": 1, "name": "Scorch Gorge", "id": "VnNTdGFnZS0x"},
+            {"vsStageId": "2", "name": "Eeltail Alley", "id": "VnNTdGFnZS0y"},
+            "not a stage",
+            {"vsStageId": 3, "name": "Hagglefish Market", "id": "VnNTdGFnZS0z"}
+        ]}"#;
+        let parsed: ScheduleContainer<VsStage> = serde_json::from_str(example).unwrap();
+        assert_eq!(
+            parsed
+                .nodes
+                .iter()
+                .map(|stage| stage.vs_stage_id)
+                .collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(parsed.skipped.len(), 2);
+        assert_eq!(
+            parsed.skipped[0],
+            SkippedNode {
+                index: 1,
+                path: "vsStageId".to_string(),
+                message: "invalid type: string \"2\", expected u32".to_string(),
+            }
+        );
+        assert_eq!(parsed.skipped[1].index, 2);
+
+        // The container itself still has to be valid
+        assert!(serde_json::from_str::<ScheduleContainer<VsStage>>(r#"{"nodes": null}"#).is_err());
+    }
+
+    #[test]
+    fn test_deserialize_gear_sale() {
+        let example = r#"{"id":"U2FsZUdlYXItQUFBQUFBQUFB","saleEndTime":"2025-12-14T04:00:00Z","price":3750,"gear":{"__typename":"HeadGear","name":"Annaki Beret","primaryGearPower":{"name":"Ink Resistance Up","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/skill_img/ink_resistance_up.png"},"__splatoon3ink_id":"4b6d3b4a0eb5a6d2"},"additionalGearPowers":[{"name":"Unknown","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/skill_img/unknown.png"},"__splatoon3ink_id":"6a4fbc1d3d1d7a1b"},{"name":"Unknown","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/skill_img/unknown.png"},"__splatoon3ink_id":"6a4fbc1d3d1d7a1b"}],"image":{"url":"https://splatoon3.ink/assets/splatnet/v3/gear_img/annaki_beret.png"},"brand":{"name":"Annaki","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/brand_img/annaki.png"},"id":"QnJhbmQtNg=="},"__splatoon3ink_id":"e1b6b7e8f2b4c3d9"},"isAlreadyOrdered":false}"#;
+        let parsed: GearSale = serde_json::from_str(example).unwrap();
+        assert_eq!(parsed.price, 3750);
+        assert_eq!(
+            parsed.sale_end_time,
+            Utc.with_ymd_and_hms(2025, 12, 14, 4, 0, 0).unwrap()
+        );
+        assert_eq!(parsed.gear.name, "Annaki Beret");
+        assert_eq!(parsed.gear.id, Some("e1b6b7e8f2b4c3d9".to_string()));
+        assert_eq!(
+            parsed.gear.primary_gear_power,
+            GearPower {
+                name: "Ink Resistance Up".to_string(),
+                id: Some("4b6d3b4a0eb5a6d2".to_string()),
+            }
+        );
+        assert_eq!(parsed.gear.additional_gear_powers.len(), 2);
+        assert_eq!(
+            parsed.gear.brand,
+            NameID {
+                name: "Annaki".to_string(),
+                id: "QnJhbmQtNg==".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_coop_monthly_gear() {
+        let example = r#"{"data":{"coopResult":{"monthlyGear":{"__splatoon3ink_id":"7a7c3bb5e8f27d0d","__typename":"HeadGear","name":"Salmonid Beanie","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/gear_img/salmonid_beanie.png"}},"pointCard":null,"regularAverageClearWave":null}}}"#;
+        let parsed: RawCoopData = serde_json::from_str(example).unwrap();
+        assert_eq!(parsed.data.coop_result.monthly_gear.name, "Salmonid Beanie");
+        assert_eq!(
+            parsed.data.coop_result.monthly_gear.id,
+            Some("7a7c3bb5e8f27d0d".to_string())
+        );
+    }
+
+    #[test]
+    fn test_deserialize_fest_record() {
+        let example = r#"{"__splatoon3ink_id":"JUEA-00001","id":"RmVzdC1VUzpKVUVBLTAwMDAx","state":"CLOSED","startTime":"2022-11-12T00:00:00Z","endTime":"2022-11-14T00:00:00Z","title":"Which would you rather bring to a deserted island?","lang":"en-US","image":{"url":"https://splatoon3.ink/assets/splatnet/v3/fest_img/fest_1.png"},"playerResult":null,"teams":[{"result":{"rank":1,"isWinner":true,"isVoteRatioTop":false,"voteRatio":0.28,"isHoragaiRatioTop":true,"horagaiRatio":0.36,"isRegularContributionRatioTop":true,"regularContributionRatio":0.35,"isChallengeContributionRatioTop":true,"challengeContributionRatio":0.37,"isTricolorContributionRatioTop":false,"tricolorContributionRatio":0.3},"id":"RmVzdFRlYW0tVVM6SlVFQS0wMDAwMTox","teamName":"Gear","color":{"a":1,"b":0.1,"g":0.7,"r":0.9},"image":{"url":"https://splatoon3.ink/assets/splatnet/v3/fest_team_img/1.png"},"myVoteState":null},{"result":null,"id":"RmVzdFRlYW0tVVM6SlVFQS0wMDAwMToy","teamName":"Grub","color":{"a":1,"b":0.6,"g":0.2,"r":0.2},"image":{"url":"https://splatoon3.ink/assets/splatnet/v3/fest_team_img/2.png"},"myVoteState":null}],"myTeam":null,"__typename":"Fest","isVotable":false}"#;
+        let parsed: FestRecord = serde_json::from_str(example).unwrap();
+        assert_eq!(parsed.splatoon3ink_id, "JUEA-00001");
+        assert_eq!(parsed.state, FestState::Closed);
+        assert_eq!(parsed.teams.len(), 2);
+        assert_eq!(parsed.teams[0].team_name, "Gear");
+        let result = parsed.teams[0].result.as_ref().unwrap();
+        assert!(result.is_winner);
+        assert_eq!(result.horagai_ratio, Some(0.36));
+        assert_eq!(result.challenge_contribution_ratio, Some(0.37));
+        assert_eq!(parsed.teams[1].result, None);
+    }
 }
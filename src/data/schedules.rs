@@ -1,12 +1,15 @@
+use std::collections::HashSet;
+
 use chrono::{self, DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+pub use super::raw_data::TimePeriod;
 use super::{
-    raw_data::{self, TimePeriod},
+    raw_data,
     translation::{Dictionary, Translatable},
 };
 
-#[allow(dead_code)] // Very easy to implement, doesn't hurt to keep them.
+/// Anything that takes place between a start and an end time.
 pub trait Schedule {
     fn get_start_time(&self) -> DateTime<Utc>;
     fn get_end_time(&self) -> DateTime<Utc>;
@@ -22,6 +25,81 @@ pub struct Schedules {
     pub work_big_run: Vec<CoopSchedule>,
     pub work_team_contest: Vec<CoopSchedule>,
     pub league: Vec<LeagueSchedule>,
+    pub fest_open: Vec<BattleSchedule>,
+    pub fest_pro: Vec<BattleSchedule>,
+    pub current_fest: Option<CurrentFest>,
+    pub vs_stages: Vec<NameID>,
+    /// Parts of the data that couldn't be understood, and were left out.
+    pub warnings: Vec<String>,
+}
+
+/// Number of upcoming rotations that should be known in advance for each battle mode.
+const MIN_UPCOMING_ROTATIONS: usize = 3;
+
+impl Schedules {
+    /// The time when new data is expected, and these schedules should be refreshed: when fewer
+    /// than `MIN_UPCOMING_ROTATIONS` upcoming rotations are left in a battle mode, or when the
+    /// last known Salmon Run shift or Challenge starts, as the next one should be announced by then.
+    ///
+    /// Returns `None` if there's nothing scheduled at all.
+    pub fn refresh_time(&self) -> Option<DateTime<Utc>> {
+        let battles = [
+            &self.regular,
+            &self.anarchy_open,
+            &self.anarchy_series,
+            &self.x_battle,
+            &self.fest_open,
+            &self.fest_pro,
+        ]
+        .into_iter()
+        .filter_map(|schedules| {
+            match schedules.len().checked_sub(MIN_UPCOMING_ROTATIONS) {
+                Some(index) if index > 0 => Some(schedules[index].start_time),
+                // Already below the threshold, wait for the current rotation to end
+                _ => schedules.first().map(|schedule| schedule.end_time),
+            }
+        });
+        let work = self.work_regular.last().map(|shift| shift.start_time);
+        let league = self
+            .league
+            .last()
+            .and_then(|league| league.time_periods.first())
+            .map(|time_period| time_period.start_time);
+
+        battles.chain(work).chain(league).min()
+    }
+
+    /// Drop everything that has ended before `time`, so that outdated data (e.g. loaded from an
+    /// expired cache) only shows what's still relevant.
+    pub fn without_ended(mut self, time: DateTime<Utc>) -> Self {
+        fn retain_ongoing<T: Schedule>(schedules: &mut Vec<T>, time: DateTime<Utc>) {
+            schedules.retain(|schedule| schedule.get_end_time() > time);
+        }
+
+        retain_ongoing(&mut self.regular, time);
+        retain_ongoing(&mut self.anarchy_open, time);
+        retain_ongoing(&mut self.anarchy_series, time);
+        retain_ongoing(&mut self.x_battle, time);
+        retain_ongoing(&mut self.work_regular, time);
+        retain_ongoing(&mut self.work_big_run, time);
+        retain_ongoing(&mut self.work_team_contest, time);
+        retain_ongoing(&mut self.fest_open, time);
+        retain_ongoing(&mut self.fest_pro, time);
+        self.league.retain(|league| {
+            league
+                .time_periods
+                .iter()
+                .any(|time_period| time_period.end_time > time)
+        });
+        if self
+            .current_fest
+            .as_ref()
+            .is_some_and(|fest| fest.end_time <= time)
+        {
+            self.current_fest = None;
+        }
+        self
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -32,6 +110,21 @@ pub struct BattleSchedule {
     pub rule: NameID,
 }
 
+impl BattleSchedule {
+    fn from_setting(
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+        setting: &raw_data::MatchSetting,
+    ) -> Self {
+        BattleSchedule {
+            start_time,
+            end_time,
+            stages: setting.vs_stages.iter().map(|stage| stage.into()).collect(),
+            rule: (&setting.vs_rule).into(),
+        }
+    }
+}
+
 impl Schedule for BattleSchedule {
     fn get_start_time(&self) -> DateTime<Utc> {
         self.start_time
@@ -47,6 +140,7 @@ pub enum CoopRule {
     Regular,
     BigRun,
     TeamContest,
+    Unknown(String),
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
@@ -79,12 +173,55 @@ pub struct LeagueSchedule {
     pub time_periods: Vec<TimePeriod>,
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct CurrentFest {
+    pub id: String,
+    pub title: String,
+    pub start_time: DateTime<Utc>,
+    pub midterm_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    pub state: FestState,
+    pub teams: Vec<FestTeam>,
+    pub tricolor_stage: Option<NameID>,
+    pub is_tricolor_active: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub enum FestState {
+    Scheduled,
+    FirstHalf,
+    SecondHalf,
+    Closed,
+    Unknown(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
+pub struct FestTeam {
+    pub id: String,
+    pub name: String,
+    pub color: FestColor,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub struct FestColor {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
 pub struct NameID {
     pub name: String,
     pub id: String,
 }
 
+impl NameID {
+    /// Whether `query` is the id, or a part of the name regardless of the case.
+    pub fn matches(&self, query: &str) -> bool {
+        self.id == query || self.name.to_lowercase().contains(&query.to_lowercase())
+    }
+}
+
 impl From<&super::raw_data::NameID> for NameID {
     fn from(value: &super::raw_data::NameID) -> Self {
         Self {
@@ -104,7 +241,7 @@ impl From<super::raw_data::NameID> for NameID {
 }
 
 impl Translatable for NameID {
-    fn translate(&self, dict: &super::translation::FlattenedTranslationDictionary) -> Self {
+    fn translate(&self, dict: &super::translation::TranslationDictionary) -> Self {
         NameID {
             name: dict.lookup(&self.id).unwrap_or(self.name.clone()),
             id: self.id.clone(),
@@ -121,9 +258,10 @@ impl From<&raw_data::CoopNode> for CoopSchedule {
             stage: (&value.match_setting.coop_stage).into(),
             rule: match &value.match_setting.rule {
                 Some(rule) => match rule.as_str() {
+                    "REGULAR" => CoopRule::Regular,
                     "TEAM_CONTEST" => CoopRule::TeamContest,
                     "BIG_RUN" => CoopRule::BigRun,
-                    _ => CoopRule::Regular,
+                    _ => CoopRule::Unknown(rule.clone()),
                 },
                 None => CoopRule::Regular,
             },
@@ -137,18 +275,53 @@ impl From<&raw_data::CoopNode> for CoopSchedule {
     }
 }
 
-impl From<&raw_data::MatchNode> for BattleSchedule {
-    fn from(value: &raw_data::MatchNode) -> Self {
-        BattleSchedule {
+impl From<raw_data::FestColor> for FestColor {
+    fn from(value: raw_data::FestColor) -> Self {
+        let to_channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        FestColor {
+            r: to_channel(value.r),
+            g: to_channel(value.g),
+            b: to_channel(value.b),
+        }
+    }
+}
+
+impl From<&raw_data::FestState> for FestState {
+    fn from(value: &raw_data::FestState) -> Self {
+        match value {
+            raw_data::FestState::Scheduled => FestState::Scheduled,
+            raw_data::FestState::FirstHalf => FestState::FirstHalf,
+            raw_data::FestState::SecondHalf => FestState::SecondHalf,
+            raw_data::FestState::Closed => FestState::Closed,
+            raw_data::FestState::Unknown(state) => FestState::Unknown(state.clone()),
+        }
+    }
+}
+
+impl From<&raw_data::CurrentFest> for CurrentFest {
+    fn from(value: &raw_data::CurrentFest) -> Self {
+        CurrentFest {
+            id: value.id.clone(),
+            title: value.title.clone(),
             start_time: value.start_time,
+            midterm_time: value.midterm_time,
             end_time: value.end_time,
-            stages: value
-                .match_setting
-                .vs_stages
+            state: (&value.state).into(),
+            teams: value
+                .teams
                 .iter()
-                .map(|stage| stage.into())
+                .enumerate()
+                .map(|(index, team)| FestTeam {
+                    id: team.id.clone(),
+                    name: team
+                        .team_name
+                        .clone()
+                        .unwrap_or_else(|| format!("Team {}", index + 1)),
+                    color: team.color.into(),
+                })
                 .collect(),
-            rule: (&value.match_setting.vs_rule).into(),
+            tricolor_stage: value.tricolor_stage.as_ref().map(|stage| stage.into()),
+            is_tricolor_active: value.is_tricolor_active,
         }
     }
 }
@@ -179,22 +352,79 @@ impl From<&raw_data::MatchNodeLeague> for LeagueSchedule {
     }
 }
 
+/// Describe the nodes of `container` that were skipped while parsing.
+fn skipped_warnings<T>(
+    name: &str,
+    container: &raw_data::ScheduleContainer<T>,
+) -> impl Iterator<Item = String> {
+    container.skipped.iter().map(move |node| {
+        format!(
+            "{name}: node {} skipped, {}",
+            node.index,
+            describe_skipped(node)
+        )
+    })
+}
+
+fn describe_skipped(node: &raw_data::SkippedNode) -> String {
+    if node.path.is_empty() || node.path == "." {
+        node.message.clone()
+    } else {
+        format!("{} at {}", node.message, node.path)
+    }
+}
+
 impl From<raw_data::RawData> for Schedules {
     fn from(value: raw_data::RawData) -> Self {
         let mut res = Self::default();
 
-        value
+        let data = &value.data;
+        let coop = &data.coop_grouping_schedule;
+        res.warnings = skipped_warnings("regularSchedules", &data.regular_schedules)
+            .chain(skipped_warnings(
+                "bankaraSchedules",
+                &data.bankara_schedules,
+            ))
+            .chain(skipped_warnings("xSchedules", &data.x_schedules))
+            .chain(skipped_warnings("eventSchedules", &data.event_schedules))
+            .chain(skipped_warnings("festSchedules", &data.fest_schedules))
+            .chain(skipped_warnings(
+                "coopRegularSchedules",
+                &coop.regular_schedules,
+            ))
+            .chain(skipped_warnings(
+                "coopBigRunSchedules",
+                &coop.big_run_schedules,
+            ))
+            .chain(skipped_warnings(
+                "coopTeamContestSchedules",
+                &coop.team_contest_schedules,
+            ))
+            .chain(skipped_warnings("vsStages", &data.vs_stages))
+            .collect();
+
+        // Regular and X battles don't have a match setting during Splatfests
+        let from_match_node = |schedule: &raw_data::MatchNode| {
+            schedule.match_setting.as_ref().map(|setting| {
+                BattleSchedule::from_setting(schedule.start_time, schedule.end_time, setting)
+            })
+        };
+
+        res.regular = value
             .data
             .regular_schedules
             .nodes
             .iter()
-            .for_each(|schedule| {
-                res.regular.push(schedule.into());
-            });
+            .filter_map(from_match_node)
+            .collect();
 
-        value.data.x_schedules.nodes.iter().for_each(|schedule| {
-            res.x_battle.push(schedule.into());
-        });
+        res.x_battle = value
+            .data
+            .x_schedules
+            .nodes
+            .iter()
+            .filter_map(from_match_node)
+            .collect();
 
         value
             .data
@@ -202,25 +432,23 @@ impl From<raw_data::RawData> for Schedules {
             .nodes
             .iter()
             .for_each(|schedule| {
-                for setting in &schedule.match_settings {
-                    let schedule_res = BattleSchedule {
-                        start_time: schedule.start_time,
-                        end_time: schedule.end_time,
-                        stages: (setting
-                            .match_setting
-                            .vs_stages
-                            .iter()
-                            .map(|stage| stage.into())
-                            .collect()),
-                        rule: (&setting.match_setting.vs_rule).into(),
-                    };
-                    match setting.bankara_mode {
+                for setting in schedule.match_settings.iter().flatten() {
+                    let schedule_res = BattleSchedule::from_setting(
+                        schedule.start_time,
+                        schedule.end_time,
+                        &setting.match_setting,
+                    );
+                    match &setting.bankara_mode {
                         raw_data::BankaraMode::Open => {
                             res.anarchy_open.push(schedule_res);
                         }
                         raw_data::BankaraMode::Challenge => {
                             res.anarchy_series.push(schedule_res);
                         }
+                        raw_data::BankaraMode::Unknown(mode) => {
+                            res.warnings
+                                .push(format!("bankaraSchedules: unknown bankaraMode {mode}"));
+                        }
                     }
                 }
             });
@@ -245,12 +473,280 @@ impl From<raw_data::RawData> for Schedules {
                 res.work_big_run.push(schedule.into());
             });
 
+        value
+            .data
+            .coop_grouping_schedule
+            .team_contest_schedules
+            .nodes
+            .iter()
+            .for_each(|schedule| {
+                res.work_team_contest.push(schedule.into());
+            });
+
+        for shift in res
+            .work_regular
+            .iter()
+            .chain(&res.work_big_run)
+            .chain(&res.work_team_contest)
+        {
+            if let CoopRule::Unknown(rule) = &shift.rule {
+                res.warnings
+                    .push(format!("coopGroupingSchedule: unknown rule {rule}"));
+            }
+        }
+
         value.data.event_schedules.nodes.iter().for_each(|event| {
             res.league.push(event.into());
         });
 
+        value.data.fest_schedules.nodes.iter().for_each(|schedule| {
+            for setting in schedule.match_settings.iter().flatten() {
+                let schedule_res = BattleSchedule::from_setting(
+                    schedule.start_time,
+                    schedule.end_time,
+                    &setting.match_setting,
+                );
+                match &setting.fest_mode {
+                    raw_data::FestMode::Regular => {
+                        res.fest_open.push(schedule_res);
+                    }
+                    raw_data::FestMode::Challenge => {
+                        res.fest_pro.push(schedule_res);
+                    }
+                    raw_data::FestMode::Unknown(mode) => {
+                        res.warnings
+                            .push(format!("festSchedules: unknown festMode {mode}"));
+                    }
+                }
+            }
+        });
+
+        res.current_fest = value
+            .data
+            .current_fest
+            .node
+            .as_ref()
+            .map(|fest| fest.into());
+        if let Some(node) = &value.data.current_fest.skipped {
+            res.warnings
+                .push(format!("currentFest: skipped, {}", describe_skipped(node)));
+        }
+        if let Some(FestState::Unknown(state)) = res.current_fest.as_ref().map(|fest| &fest.state) {
+            res.warnings
+                .push(format!("currentFest: unknown state {state}"));
+        }
+
+        let mut vs_stages: Vec<&raw_data::VsStage> = value.data.vs_stages.nodes.iter().collect();
+        vs_stages.sort_by_key(|stage| stage.vs_stage_id);
+        res.vs_stages = vs_stages
+            .into_iter()
+            .map(|stage| NameID {
+                name: stage.name.clone(),
+                id: stage.id.clone(),
+            })
+            .collect();
+
+        // Unknown values are usually repeated in every node, only report them once
+        let mut reported = HashSet::new();
+        res.warnings
+            .retain(|warning| reported.insert(warning.clone()));
+
         res
     }
 }
 
-//TODO: Add tests
+#[cfg(test)]
+mod test {
+    use chrono::{TimeZone, Utc};
+
+    use crate::data::raw_data::{self, RawData};
+
+    use super::{CoopRule, FestColor, NameID, Schedules};
+
+    fn parse_sample(sample: &str) -> Schedules {
+        let raw: RawData = serde_json::from_str(sample).unwrap();
+        raw.into()
+    }
+
+    #[test]
+    fn test_schedules_from_team_contest_sample() {
+        let schedules = parse_sample(include_str!(
+            "../../resources/samples/teamcontest/schedules.json"
+        ));
+
+        assert_eq!(schedules.work_regular.len(), 4);
+        assert!(schedules.work_big_run.is_empty());
+        assert_eq!(schedules.work_team_contest.len(), 1);
+
+        let team_contest = &schedules.work_team_contest[0];
+        assert_eq!(team_contest.rule, CoopRule::TeamContest);
+        assert_eq!(
+            team_contest.start_time,
+            Utc.with_ymd_and_hms(2025, 11, 8, 0, 0, 0).unwrap()
+        );
+        assert_eq!(
+            team_contest.end_time,
+            Utc.with_ymd_and_hms(2025, 11, 10, 0, 0, 0).unwrap()
+        );
+        assert_eq!(team_contest.boss, None);
+        assert_eq!(
+            team_contest.stage,
+            NameID {
+                name: "Gone Fission Hydroplant".to_string(),
+                id: "Q29vcFN0YWdlLTc=".to_string(),
+            }
+        );
+        assert_eq!(team_contest.weapons.len(), 4);
+    }
+
+    #[test]
+    fn test_schedules_from_big_run_sample() {
+        let schedules = parse_sample(include_str!(
+            "../../resources/samples/bigrun/schedules.json"
+        ));
+
+        assert_eq!(schedules.work_regular.len(), 4);
+        assert_eq!(schedules.work_big_run.len(), 1);
+        assert!(schedules.work_team_contest.is_empty());
+        assert_eq!(schedules.work_big_run[0].rule, CoopRule::BigRun);
+        assert!(schedules.fest_open.is_empty());
+        assert!(schedules.fest_pro.is_empty());
+        assert_eq!(schedules.current_fest, None);
+        assert_eq!(schedules.vs_stages.len(), 25);
+        assert!(schedules.warnings.is_empty());
+        assert_eq!(
+            schedules.vs_stages.first(),
+            Some(&NameID {
+                name: "Scorch Gorge".to_string(),
+                id: "VnNTdGFnZS0x".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_schedules_partial_data() {
+        let mut sample: serde_json::Value = serde_json::from_str(include_str!(
+            "../../resources/samples/bigrun/schedules.json"
+        ))
+        .unwrap();
+        let data = &mut sample["data"];
+        data["regularSchedules"]["nodes"][1]["regularMatchSetting"] = 42.into();
+        for node in data["bankaraSchedules"]["nodes"].as_array_mut().unwrap() {
+            node["bankaraMatchSettings"][0]["bankaraMode"] = "TOURNAMENT".into();
+        }
+        data["coopGroupingSchedule"]["bigRunSchedules"]["nodes"][0]["setting"]["rule"] =
+            "MEGA_RUN".into();
+        data["currentFest"] = serde_json::json!({"title": "Which is the best?", "state": 5});
+
+        let complete = parse_sample(include_str!(
+            "../../resources/samples/bigrun/schedules.json"
+        ));
+        let raw: RawData = serde_json::from_value(sample).unwrap();
+        let schedules: Schedules = raw.into();
+
+        assert_eq!(schedules.regular.len(), complete.regular.len() - 1);
+        assert_eq!(schedules.regular[0], complete.regular[0]);
+        assert_eq!(schedules.regular[1], complete.regular[2]);
+        assert_eq!(schedules.x_battle, complete.x_battle);
+        assert_eq!(
+            schedules.anarchy_open.len() + schedules.anarchy_series.len(),
+            complete.anarchy_open.len()
+        );
+        assert_eq!(
+            schedules.work_big_run[0].rule,
+            CoopRule::Unknown("MEGA_RUN".to_string())
+        );
+        assert_eq!(schedules.current_fest, None);
+        assert_eq!(
+            schedules.warnings,
+            vec![
+                "regularSchedules: node 1 skipped, invalid type: integer `42`, expected struct \
+                 MatchSetting at regularMatchSetting"
+                    .to_string(),
+                "bankaraSchedules: unknown bankaraMode TOURNAMENT".to_string(),
+                "coopGroupingSchedule: unknown rule MEGA_RUN".to_string(),
+                "currentFest: skipped, data did not match any variant of untagged enum FestState at state"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schedules_without_ended() {
+        let schedules = parse_sample(include_str!(
+            "../../resources/samples/teamcontest/schedules.json"
+        ));
+
+        let unchanged = schedules
+            .clone()
+            .without_ended(Utc.with_ymd_and_hms(2025, 11, 8, 0, 0, 0).unwrap());
+        assert_eq!(unchanged, schedules);
+
+        let pruned = schedules
+            .clone()
+            .without_ended(Utc.with_ymd_and_hms(2025, 11, 9, 0, 30, 0).unwrap());
+        assert_eq!(pruned.regular.len(), 6);
+        assert!(pruned.regular.iter().all(
+            |schedule| schedule.end_time > Utc.with_ymd_and_hms(2025, 11, 9, 0, 30, 0).unwrap()
+        ));
+        assert_eq!(pruned.league.len(), 2);
+
+        let pruned = schedules.without_ended(Utc.with_ymd_and_hms(2025, 11, 20, 0, 0, 0).unwrap());
+        assert!(pruned.regular.is_empty());
+        assert!(pruned.work_team_contest.is_empty());
+        // Only the second event has time periods left
+        assert_eq!(pruned.league.len(), 1);
+        // The stage pool doesn't expire
+        assert!(!pruned.vs_stages.is_empty());
+    }
+
+    #[test]
+    fn test_schedules_refresh_time() {
+        let schedules = parse_sample(include_str!(
+            "../../resources/samples/teamcontest/schedules.json"
+        ));
+        // 3 rotations before the end of the 12 known ones
+        assert_eq!(
+            schedules.refresh_time(),
+            Some(Utc.with_ymd_and_hms(2025, 11, 9, 6, 0, 0).unwrap())
+        );
+
+        // Only the current rotation and an upcoming one are known
+        let mut thin = schedules.clone();
+        thin.regular.truncate(2);
+        assert_eq!(
+            thin.refresh_time(),
+            Some(Utc.with_ymd_and_hms(2025, 11, 8, 14, 0, 0).unwrap())
+        );
+
+        // Only Salmon Run shifts are known
+        let work_only = Schedules {
+            work_regular: schedules.work_regular.clone(),
+            ..Default::default()
+        };
+        assert_eq!(
+            work_only.refresh_time(),
+            Some(Utc.with_ymd_and_hms(2025, 11, 13, 0, 0, 0).unwrap())
+        );
+
+        assert_eq!(Schedules::default().refresh_time(), None);
+    }
+
+    #[test]
+    fn test_fest_color_conversion() {
+        let color: FestColor = raw_data::FestColor {
+            r: 1.0,
+            g: 0.5,
+            b: 0.0,
+        }
+        .into();
+        assert_eq!(
+            color,
+            FestColor {
+                r: 255,
+                g: 128,
+                b: 0
+            }
+        );
+    }
+}
@@ -0,0 +1,335 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use chrono::Duration;
+use color_eyre::{Report, Result, eyre::eyre};
+use futures::{FutureExt, future::BoxFuture};
+use reqwest::{
+    StatusCode, Url,
+    header::{self, HeaderMap},
+};
+use serde::{Deserialize, Serialize};
+
+use super::{APP_USER_AGENT, DataError};
+
+pub const DEFAULT_BASE_URL: &str = "https://splatoon3.ink/data/";
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Where the JSON documents are loaded from.
+///
+/// Paths are relative to the data root, following the layout of <https://splatoon3.ink/data/>,
+/// e.g. `schedules.json` or `locale/zh-CN.json`.
+pub trait DataSource: Send + Sync {
+    fn fetch<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>>;
+
+    /// Fetch `path`, unless it hasn't changed since it was fetched with `validators`.
+    ///
+    /// Sources that don't support conditional requests always return the whole document.
+    fn fetch_if_modified<'a>(
+        &'a self,
+        path: &'a str,
+        _validators: &'a Validators,
+    ) -> BoxFuture<'a, Result<Fetched<String>>> {
+        self.fetch(path)
+            .map(|res| res.map(|body| Fetched::Modified(Document::new(body))))
+            .boxed()
+    }
+}
+
+/// `ETag` and `Last-Modified` of a fetched document, used to make conditional requests.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let get_header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.to_string())
+        };
+        Validators {
+            etag: get_header(header::ETAG),
+            last_modified: get_header(header::LAST_MODIFIED),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document<T> {
+    pub value: T,
+    pub validators: Validators,
+    /// How long the document can be used without being revalidated, from `Cache-Control`.
+    pub max_age: Option<Duration>,
+}
+
+impl<T> Document<T> {
+    pub fn new(value: T) -> Self {
+        Document {
+            value,
+            validators: Validators::default(),
+            max_age: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fetched<T> {
+    Modified(Document<T>),
+    NotModified { max_age: Option<Duration> },
+}
+
+/// Parse the `max-age` directive of a `Cache-Control` header. Values that don't fit in a
+/// `Duration` are ignored.
+fn parse_max_age(cache_control: &str) -> Option<Duration> {
+    cache_control.split(',').find_map(|directive| {
+        let seconds = directive.trim().strip_prefix("max-age=")?;
+        Duration::try_seconds(seconds.trim_matches('"').parse().ok()?)
+    })
+}
+
+/// Fetch data from splatoon3.ink, or a mirror of it.
+pub struct HttpSource {
+    client: reqwest::Client,
+    base_url: Url,
+}
+
+impl HttpSource {
+    pub fn new(base_url: &str) -> Result<Self> {
+        // Without the trailing slash, the last segment would be replaced when joining paths.
+        let base_url = if base_url.ends_with('/') {
+            Url::parse(base_url)?
+        } else {
+            Url::parse(&format!("{base_url}/"))?
+        };
+        let client = reqwest::Client::builder()
+            .user_agent(APP_USER_AGENT)
+            .timeout(REQUEST_TIMEOUT)
+            .build()?;
+
+        Ok(HttpSource { client, base_url })
+    }
+}
+
+impl DataSource for HttpSource {
+    fn fetch<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+        async move {
+            let url = self.base_url.join(path)?;
+            let response = self
+                .client
+                .get(url.clone())
+                .send()
+                .await
+                .map_err(|err| map_request_error(err, &url))?;
+            let response = check_status(response, &url)?;
+            response
+                .text()
+                .await
+                .map_err(|err| map_request_error(err, &url))
+        }
+        .boxed()
+    }
+
+    fn fetch_if_modified<'a>(
+        &'a self,
+        path: &'a str,
+        validators: &'a Validators,
+    ) -> BoxFuture<'a, Result<Fetched<String>>> {
+        async move {
+            let url = self.base_url.join(path)?;
+            let mut request = self.client.get(url.clone());
+            if let Some(etag) = &validators.etag {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &validators.last_modified {
+                request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|err| map_request_error(err, &url))?;
+
+            let max_age = response
+                .headers()
+                .get(header::CACHE_CONTROL)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_max_age);
+            if response.status() == StatusCode::NOT_MODIFIED {
+                return Ok(Fetched::NotModified { max_age });
+            }
+
+            let response = check_status(response, &url)?;
+            let validators = Validators::from_headers(response.headers());
+            Ok(Fetched::Modified(Document {
+                value: response
+                    .text()
+                    .await
+                    .map_err(|err| map_request_error(err, &url))?,
+                validators,
+                max_age,
+            }))
+        }
+        .boxed()
+    }
+}
+
+/// Classify connection failures and timeouts, other errors are reported as is.
+fn map_request_error(err: reqwest::Error, url: &Url) -> Report {
+    let url = url.to_string();
+    if err.is_timeout() {
+        Report::new(DataError::Timeout { url })
+    } else if err.is_connect() {
+        Report::new(DataError::NetworkUnreachable { url })
+    } else {
+        Report::new(err)
+    }
+}
+
+/// Turn non-2xx responses into `DataError::HttpStatus`, instead of trying to parse error pages.
+fn check_status(response: reqwest::Response, url: &Url) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        Err(Report::new(DataError::HttpStatus {
+            url: url.to_string(),
+            status: status.as_u16(),
+        }))
+    }
+}
+
+/// Read data from a local directory, such as `resources/samples/bigrun`.
+pub struct LocalSource {
+    root: PathBuf,
+}
+
+impl LocalSource {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalSource { root: root.into() }
+    }
+}
+
+impl DataSource for LocalSource {
+    fn fetch<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+        async move {
+            let file_path = self.root.join(path);
+            tokio::fs::read_to_string(&file_path)
+                .await
+                .map_err(|err| eyre!("Failed to read {}: {err}", file_path.display()))
+        }
+        .boxed()
+    }
+}
+
+/// Serve fixed documents from memory, useful for demos and tests.
+#[derive(Default)]
+pub struct MemorySource {
+    documents: HashMap<String, String>,
+}
+
+impl MemorySource {
+    pub fn insert(&mut self, path: impl Into<String>, document: impl Into<String>) {
+        self.documents.insert(path.into(), document.into());
+    }
+}
+
+impl<const N: usize> From<[(&str, &str); N]> for MemorySource {
+    fn from(value: [(&str, &str); N]) -> Self {
+        let mut res = MemorySource::default();
+        for (path, document) in value {
+            res.insert(path, document);
+        }
+        res
+    }
+}
+
+impl DataSource for MemorySource {
+    fn fetch<'a>(&'a self, path: &'a str) -> BoxFuture<'a, Result<String>> {
+        let res = self
+            .documents
+            .get(path)
+            .cloned()
+            .ok_or_else(|| eyre!("{path} is not available in memory"));
+        async move { res }.boxed()
+    }
+}
+
+/// Parse the `--source` argument: URLs are fetched over HTTP, anything else is treated as a local
+/// directory.
+pub fn parse_source(source: Option<&str>) -> Result<Arc<dyn DataSource>> {
+    match source {
+        None => Ok(Arc::new(HttpSource::new(DEFAULT_BASE_URL)?)),
+        Some(source) if source.starts_with("http://") || source.starts_with("https://") => {
+            Ok(Arc::new(HttpSource::new(source)?))
+        }
+        Some(path) => Ok(Arc::new(LocalSource::new(path))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use super::{
+        DataSource, Fetched, HttpSource, LocalSource, MemorySource, Validators, parse_max_age,
+    };
+
+    #[test]
+    fn test_http_source_base_url() {
+        let source = HttpSource::new("https://example.com/mirror").unwrap();
+        assert_eq!(
+            source.base_url.join("locale/zh-CN.json").unwrap().as_str(),
+            "https://example.com/mirror/locale/zh-CN.json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_local_source() {
+        let source = LocalSource::new("resources/samples/bigrun");
+        let res = source.fetch("schedules.json").await.unwrap();
+        assert!(res.starts_with('{'));
+        assert!(source.fetch("non-existent.json").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_memory_source() {
+        let source = MemorySource::from([("schedules.json", "{}")]);
+        assert_eq!(source.fetch("schedules.json").await.unwrap(), "{}");
+        assert!(source.fetch("gear.json").await.is_err());
+
+        // Conditional requests aren't supported, the whole document is always returned
+        let validators = Validators {
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+        };
+        match source
+            .fetch_if_modified("schedules.json", &validators)
+            .await
+            .unwrap()
+        {
+            Fetched::Modified(document) => {
+                assert_eq!(document.value, "{}");
+                assert_eq!(document.validators, Validators::default());
+            }
+            Fetched::NotModified { .. } => panic!("Memory source should always return a document"),
+        }
+    }
+
+    #[test]
+    fn test_parse_max_age() {
+        assert_eq!(
+            parse_max_age("public, max-age=300"),
+            Some(Duration::seconds(300))
+        );
+        assert_eq!(
+            parse_max_age("max-age=\"60\", must-revalidate"),
+            Some(Duration::seconds(60))
+        );
+        assert_eq!(parse_max_age("no-cache"), None);
+        assert_eq!(parse_max_age("max-age=soon"), None);
+        assert_eq!(parse_max_age("max-age=31536000"), Some(Duration::days(365)));
+        assert_eq!(parse_max_age("max-age=9223372036854775807"), None);
+    }
+}
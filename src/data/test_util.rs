@@ -0,0 +1,34 @@
+//! Fixtures shared by the tests of the `data` modules.
+use chrono::{TimeZone, Utc};
+
+use super::schedules::{BattleSchedule, CoopRule, CoopSchedule, NameID};
+
+/// A name that is its own id, to keep the fixtures short.
+pub(crate) fn name_id(name: &str) -> NameID {
+    NameID {
+        name: name.to_string(),
+        id: name.to_string(),
+    }
+}
+
+/// Splat Zones on Scorch Gorge and Eeltail Alley, on 2025-11-08 from 12:00 to 14:00 UTC.
+pub(crate) fn get_test_battle() -> BattleSchedule {
+    BattleSchedule {
+        start_time: Utc.with_ymd_and_hms(2025, 11, 8, 12, 0, 0).unwrap(),
+        end_time: Utc.with_ymd_and_hms(2025, 11, 8, 14, 0, 0).unwrap(),
+        stages: vec![name_id("Scorch Gorge"), name_id("Eeltail Alley")],
+        rule: name_id("Splat Zones"),
+    }
+}
+
+/// A Big Run on Spawning Grounds against Cohozuna, from 2025-11-08 to 2025-11-10 UTC.
+pub(crate) fn get_test_big_run() -> CoopSchedule {
+    CoopSchedule {
+        rule: CoopRule::BigRun,
+        start_time: Utc.with_ymd_and_hms(2025, 11, 8, 0, 0, 0).unwrap(),
+        end_time: Utc.with_ymd_and_hms(2025, 11, 10, 0, 0, 0).unwrap(),
+        boss: Some(name_id("Cohozuna")),
+        stage: name_id("Spawning Grounds"),
+        weapons: vec![name_id("Splattershot"), name_id("Random")],
+    }
+}
@@ -3,11 +3,12 @@ use std::collections::HashMap;
 
 use super::DataError;
 
-pub(super) trait Translatable {
-    fn translate(&self, dict: &FlattenedTranslationDictionary) -> Self;
+/// Data with names that can be looked up in a `TranslationDictionary`.
+pub trait Translatable {
+    fn translate(&self, dict: &TranslationDictionary) -> Self;
 }
 
-pub(super) trait Dictionary {
+pub trait Dictionary {
     fn lookup(&self, id: &str) -> Result<String, DataError>;
 }
 
@@ -23,10 +24,62 @@ impl Dictionary for FlattenedTranslationDictionary {
     }
 }
 
+/// Translations of every text of the Challenge events, keyed by the event's id.
+pub type EventTranslationMap = HashMap<String, EventTranslationContent>;
+
+/// Holds the names in a `FlattenedTranslationDictionary`, and the texts that are more than a
+/// name (e.g. the description and regulation of Challenges) in their own maps.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TranslationDictionary {
+    pub names: FlattenedTranslationDictionary,
+    pub events: EventTranslationMap,
+}
+
+impl TranslationDictionary {
+    pub fn lookup_event(&self, id: &str) -> Result<&EventTranslationContent, DataError> {
+        self.events
+            .get(id)
+            .ok_or_else(|| DataError::TranslationError(id.to_string()))
+    }
+}
+
+impl Dictionary for TranslationDictionary {
+    fn lookup(&self, id: &str) -> Result<String, DataError> {
+        self.names.lookup(id)
+    }
+}
+
+impl From<FlattenedTranslationDictionary> for TranslationDictionary {
+    fn from(value: FlattenedTranslationDictionary) -> Self {
+        TranslationDictionary {
+            names: value,
+            events: EventTranslationMap::new(),
+        }
+    }
+}
+
+impl From<TranslationData> for TranslationDictionary {
+    fn from(mut value: TranslationData) -> Self {
+        let events = std::mem::take(&mut value.events);
+        TranslationDictionary {
+            names: value.into(),
+            events,
+        }
+    }
+}
+
 impl From<TranslationData> for FlattenedTranslationDictionary {
     fn from(value: TranslationData) -> Self {
         let mut res: HashMap<String, String> = HashMap::new();
-        for translation_map in [value.bosses, value.stages, value.rules, value.weapons] {
+        for translation_map in [
+            value.bosses,
+            value.stages,
+            value.rules,
+            value.weapons,
+            value.brands,
+            value.gear,
+            value.powers,
+        ] {
             translation_map
                 .iter()
                 .for_each(|(id, translation_content)| {
@@ -34,20 +87,37 @@ impl From<TranslationData> for FlattenedTranslationDictionary {
                 })
         }
 
+        for (id, festival) in value.festivals {
+            res.insert(id.clone(), festival.title);
+            festival
+                .teams
+                .into_iter()
+                .enumerate()
+                .for_each(|(index, team)| {
+                    res.insert(festival_team_key(&id, index), team.team_name);
+                });
+        }
+
         res
     }
 }
 
+/// Splatfest teams don't have their own ids in the translation data, so they are stored with
+/// the festival's id and the team's index.
+pub fn festival_team_key(festival_id: &str, index: usize) -> String {
+    format!("{festival_id}/teams/{index}")
+}
+
 #[derive(Deserialize, Debug)]
 pub struct TranslationData {
     stages: TranslationMap,
     rules: TranslationMap,
     weapons: TranslationMap,
-    // brands: TranslationMap,
-    // gear: TranslationMap,
-    // powers: TranslationMap,
-    // festivals: TranslationMap,
-    // events: TranslationMap,
+    brands: TranslationMap,
+    gear: TranslationMap,
+    powers: TranslationMap,
+    festivals: HashMap<String, FestivalTranslationContent>,
+    events: EventTranslationMap,
     bosses: TranslationMap,
 }
 
@@ -56,10 +126,38 @@ pub struct TranslationContent {
     name: String,
 }
 
+#[derive(Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct EventTranslationContent {
+    pub name: String,
+    #[serde(default)]
+    pub desc: String,
+    #[serde(default)]
+    pub regulation: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+pub struct FestivalTranslationContent {
+    title: String,
+    #[serde(default)]
+    teams: Vec<FestivalTeamTranslationContent>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FestivalTeamTranslationContent {
+    team_name: String,
+}
+
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
     use crate::data::translation::Dictionary;
-    use crate::data::translation::{FlattenedTranslationDictionary, TranslationContent};
+    use crate::data::translation::{
+        EventTranslationContent, EventTranslationMap, FestivalTranslationContent,
+        FlattenedTranslationDictionary, TranslationContent, TranslationDictionary,
+        festival_team_key,
+    };
 
     use super::TranslationMap;
 
@@ -119,4 +217,56 @@ mod test {
 
         assert!(dict.lookup("nonexistent").is_err());
     }
+
+    #[test]
+    fn test_festival_translation_content() {
+        let example_festivals_data = r#"
+        {
+            "JUEA-00001": {
+              "title": "你会带什么去荒岛？",
+              "teams": [
+                { "teamName": "装备" },
+                { "teamName": "食物" },
+                { "teamName": "娱乐" }
+              ]
+            }
+        }"#;
+        let result: HashMap<String, FestivalTranslationContent> =
+            serde_json::from_str(example_festivals_data).unwrap();
+        let festival = &result["JUEA-00001"];
+        assert_eq!(festival.title, "你会带什么去荒岛？");
+        assert_eq!(festival.teams.len(), 3);
+        assert_eq!(festival.teams[1].team_name, "食物");
+        assert_eq!(festival_team_key("JUEA-00001", 1), "JUEA-00001/teams/1");
+    }
+
+    #[test]
+    fn test_event_translation_lookup() {
+        let example_events_data = r#"
+        {
+            "TGVhZ3VlTWF0Y2hFdmVudC1TcGVjaWFsUnVzaF9VbHRyYVNob3Q=": {
+              "name": "三重导弹大乱斗！",
+              "desc": "用三重导弹一决胜负！",
+              "regulation": "只能使用搭载三重导弹的武器。<br />・ 特殊武器量表会自动增加！"
+            }
+        }"#;
+        let events: EventTranslationMap = serde_json::from_str(example_events_data).unwrap();
+        let dict = TranslationDictionary {
+            names: FlattenedTranslationDictionary::new(),
+            events,
+        };
+
+        assert_eq!(
+            dict.lookup_event("TGVhZ3VlTWF0Y2hFdmVudC1TcGVjaWFsUnVzaF9VbHRyYVNob3Q=")
+                .unwrap(),
+            &EventTranslationContent {
+                name: "三重导弹大乱斗！".to_string(),
+                desc: "用三重导弹一决胜负！".to_string(),
+                regulation: "只能使用搭载三重导弹的武器。<br />・ 特殊武器量表会自动增加！"
+                    .to_string(),
+            }
+        );
+        assert!(dict.lookup_event("nonexistent").is_err());
+        assert!(dict.lookup("nonexistent").is_err());
+    }
 }
@@ -0,0 +1,38 @@
+//! Splatoon 3 schedules from <https://splatoon3.ink/>, used by the idacast TUI.
+//!
+//! [`data`] fetches and parses the schedules, the SplatNet shop, the Salmon Run rewards and the
+//! Splatfests from a [`DataSource`](data::source::DataSource), and translates them with a
+//! [`TranslationDictionary`], loaded with [`get_dictionary`]. [`cache`] stores them on
+//! disk, and keeps track of when they should be refreshed.
+//!
+//! Rotations can be looked up across all modes with the methods of [`Schedules`] from
+//! [`data::query`], e.g. [`Schedules::at`] or [`Schedules::find`].
+//!
+//! ```no_run
+//! use idacast::data::{get_schedules, source::HttpSource, source::DEFAULT_BASE_URL};
+//!
+//! # async fn run() -> color_eyre::Result<()> {
+//! let source = HttpSource::new(DEFAULT_BASE_URL)?;
+//! let schedules = get_schedules(&source, Some("ja-JP".to_string())).await?;
+//! if let Some(rotation) = schedules.regular.first() {
+//!     println!("{}: {:?}", rotation.rule.name, rotation.stages);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Features
+//!
+//! * `cache`: The [`cache`] module, backed by a disk cache.
+//! * `tui`: The idacast binary, enabled by default along with `cache`. Library users can disable
+//!   the default features to avoid the terminal dependencies.
+
+#[cfg(feature = "cache")]
+pub mod cache;
+pub mod data;
+
+pub use data::{
+    DataError, get_dictionary, get_schedules,
+    schedules::{BattleSchedule, CoopSchedule, LeagueSchedule, Schedules},
+    translation::TranslationDictionary,
+};
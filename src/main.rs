@@ -1,25 +1,55 @@
-use std::io::stdout;
+use std::{io::stdout, path::PathBuf};
 
-use app::CACHE_STORE_NAME;
+use app::{
+    AppScreen, CACHE_STORE_NAME, COOP_CACHE_STORE_NAME, FESTIVALS_CACHE_STORE_NAME,
+    GEAR_CACHE_STORE_NAME, theme::ThemeName,
+};
 use cached::DiskCache;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use cli::OutputFormat;
 use color_eyre::Result;
+use config::{Config, ConfigCommand, PreferenceMarks};
 use crossterm::{ExecutableCommand, event};
-use data::schedules::Schedules;
+use idacast::data::{self, festivals::Region};
 
 mod app;
-mod data;
+mod cli;
+mod config;
 
 #[derive(Parser, Debug)]
 #[command(version, about)]
 /// This program displays Splatoon 3's stage data from a terminal user interface.
 /// Operating System's language setting is read to automatically determine the translation to use.
 /// Data is fetched from <https://splatoon3.ink/>
+///
+/// Settings are read from `$XDG_CONFIG_HOME/idacast/config.toml`, see `idacast config init`. The
+/// environment variables and the options take precedence over the config file.
 struct Args {
+    /// Config file to use instead of the default one.
+    #[arg(long, value_name = "FILE", env = "IDACAST_CONFIG", global = true)]
+    config: Option<PathBuf>,
     /// Optional language to use when fetching translations. If set, will take precedence over
     /// OS's language setting.
-    #[arg(short, long)]
+    #[arg(short, long, env = "IDACAST_LANGUAGE", global = true)]
     language: Option<String>,
+    /// Region of the Splatfests to display in the festivals tab: US, EU, JP or AP. Defaults to US.
+    #[arg(long, env = "IDACAST_REGION", global = true)]
+    region: Option<Region>,
+    /// Colors to use: dark, light, high-contrast or monochrome. `NO_COLOR` is respected unless a
+    /// color depth is set in the config file.
+    #[arg(long, env = "IDACAST_THEME", global = true)]
+    theme: Option<ThemeName>,
+    /// Tab to display on startup, e.g. work or stages.
+    #[arg(long, env = "IDACAST_TAB", global = true)]
+    tab: Option<AppScreen>,
+    /// Where to load the data from. Accepts the base URL of a splatoon3.ink mirror, or a local
+    /// directory with the same layout (e.g. `schedules.json`, `locale/zh-CN.json`).
+    /// Defaults to <https://splatoon3.ink/data/>.
+    #[arg(long, value_name = "URL|DIR", env = "IDACAST_SOURCE", global = true)]
+    source: Option<String>,
+    /// Never fetch data, only display what's in the cache, however old it is.
+    #[arg(long, conflicts_with = "source", global = true)]
+    offline: bool,
     /// Mouse capture is enabled by default, so that you can use mouse buttons to manipluate the
     /// display. Supply this option to disable it.
     #[arg(long)]
@@ -27,21 +57,65 @@ struct Args {
     /// Tries to clear the network cache
     #[arg(long)]
     clear_cache: bool,
+    /// Output format of the subcommands: text, json, ndjson or yaml.
+    #[arg(long, default_value_t = OutputFormat::Text, global = true)]
+    format: OutputFormat,
+    /// Print the schedules and exit, instead of starting the TUI.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    #[command(flatten)]
+    Schedules(cli::Command),
+    /// Create or print the config file.
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
 }
 
 impl Args {
-    fn infer_language(&mut self) {
-        match &self.language {
-            Some(_) => {}
-            None => self.language = sys_locale::get_locale(),
-        };
+    /// Override the settings of `config` with the ones given on the command line or in the
+    /// environment.
+    fn apply(&self, config: &mut Config) {
+        if let Some(language) = &self.language {
+            config.language = Some(language.clone());
+        }
+        if let Some(region) = self.region {
+            config.region = region;
+        }
+        if let Some(theme) = self.theme {
+            config.theme.name = theme;
+        }
+        if let Some(tab) = self.tab {
+            config.tabs.default = Some(tab);
+        }
+        if let Some(source) = &self.source {
+            config.source = Some(source.clone());
+            config.offline = false;
+        }
+        if self.offline {
+            config.offline = true;
+        }
+        if self.no_mouse {
+            config.mouse = false;
+        }
     }
 }
 
 fn clear_cache() -> Result<()> {
-    let mut cache_db = DiskCache::<String, Schedules>::new(CACHE_STORE_NAME).build()?;
-    let connection = cache_db.connection_mut();
-    connection.clear()?;
+    for name in [
+        CACHE_STORE_NAME,
+        GEAR_CACHE_STORE_NAME,
+        COOP_CACHE_STORE_NAME,
+        FESTIVALS_CACHE_STORE_NAME,
+    ] {
+        // The value type doesn't matter when clearing the whole store
+        let mut cache_db = DiskCache::<String, ()>::new(name).build()?;
+        cache_db.connection_mut().clear()?;
+    }
     Ok(())
 }
 
@@ -50,19 +124,57 @@ async fn main() -> Result<()> {
     color_eyre::install()?;
 
     // Parse arguments and setup
-    let mut args = Args::parse();
-    args.infer_language();
+    let args = Args::parse();
+    let config_path = match &args.config {
+        Some(path) => path.clone(),
+        None => Config::default_path()?,
+    };
+    let load_config = || {
+        let mut config = Config::load(&config_path)?;
+        args.apply(&mut config);
+        Ok(config)
+    };
+
+    // Before validating the config, so that an invalid one can be fixed
+    if let Some(Command::Config { action }) = &args.command {
+        return config::run(action.clone(), &config_path, load_config);
+    }
+    let mut config = load_config()?;
+    config.validate()?;
+    if config.language.is_none() {
+        config.language = sys_locale::get_locale();
+    }
 
     if args.clear_cache {
         return clear_cache();
     }
 
+    let source = if config.offline {
+        None
+    } else {
+        Some(data::source::parse_source(config.source.as_deref())?)
+    };
+
+    if let Some(Command::Schedules(command)) = args.command {
+        let cache_key = app::cache_key(config.source.as_deref(), &config.language);
+        return cli::run(
+            command,
+            args.format,
+            source.as_deref(),
+            config.language,
+            &cache_key,
+        )
+        .await;
+    }
+
+    let mouse = config.mouse;
+    let mut app = app::App::new(config, PreferenceMarks::default_path()?, source)?;
     let mut terminal = ratatui::init();
-    if !args.no_mouse {
+    if mouse {
         stdout().execute(event::EnableMouseCapture)?;
     }
 
-    let result = app::App::new(args.language).run(&mut terminal).await;
+    let result = app.run(&mut terminal).await;
 
     ratatui::restore();
     stdout().execute(event::DisableMouseCapture)?;